@@ -1,3 +1,6 @@
+mod rng;
+pub use rng::Rng;
+
 #[allow(dead_code)]
 
 pub trait Ternary {
@@ -0,0 +1,58 @@
+// cargo bench --features headless
+//
+// Times World::step with every broadphase for a growing number of balls.
+// The pair counts of the first step have to match, otherwise a broadphase
+// is missing contacts.
+
+use std::time::Instant;
+
+use physim::broadphase::BroadphaseKind;
+use physim::scene::Scene;
+use physim::world::World;
+
+const SEED: u64 = 1234;
+const STEPS: u32 = 20;
+const KINDS: [BroadphaseKind; 3] = [
+    BroadphaseKind::SpatialHash,
+    BroadphaseKind::SweepAndPrune,
+    BroadphaseKind::BruteForce,
+];
+
+// square world with room for about four balls per ball
+fn world(num_balls: usize, kind: BroadphaseKind) -> World {
+    let side = (num_balls as f32).sqrt() * 2.0;
+    let scene = Scene {
+        bounds: raylib::Vector2::new_from(side),
+        random_balls: num_balls,
+        ..Scene::default()
+    };
+    let mut world = World::new(SEED, scene);
+    world.broadphase.kind = kind;
+    world
+}
+
+fn main() {
+    println!("{:>8} {:>16} {:>10} {:>12}", "balls", "broadphase", "pairs", "ms / step");
+
+    for num_balls in [256, 1_000, 10_000] {
+        let mut baseline = None;
+
+        for kind in KINDS {
+            let mut world = world(num_balls, kind);
+            let pairs = world.broadphase.find_pairs(&world.balls).len();
+
+            let start = Instant::now();
+            for _ in 0..STEPS {
+                world.step(world.timestep.dt());
+            }
+            let ms = start.elapsed().as_secs_f64() * 1000.0 / STEPS as f64;
+
+            println!("{:>8} {:>16} {:>10} {:>12.3}", num_balls, kind.name(), pairs, ms);
+
+            match baseline {
+                None => baseline = Some(pairs),
+                Some(expected) => assert_eq!(expected, pairs, "{} disagrees", kind.name()),
+            }
+        }
+    }
+}
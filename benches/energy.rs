@@ -0,0 +1,89 @@
+// cargo bench --features headless --bench energy
+//
+// How far each integrator lets the energy of a single ball drift, the case
+// NUM_OF_BALLS == 1 used to print. In flight the ball never touches anything,
+// bouncing it falls onto the floor over and over with nothing lost in the
+// bounces. Drift is in % of the energy it started with, worst is the furthest
+// it got at any step.
+
+use physim::ball::Ball;
+use physim::integrator::Integrator;
+use physim::material::{Combine, Friction, Material};
+use physim::player::Player;
+use physim::scene::Scene;
+use physim::sleep::energy;
+use physim::world::World;
+
+const SEED: u64 = 1234;
+const SECONDS: f32 = 10.0;
+
+// bounces back at full speed and doesn't slip or spin
+const ELASTIC: Material = Material {
+    restitution: 1.0,
+    friction: Friction::new(0.0, 0.0, 0.0),
+    density: 0.25,
+    restitution_combine: Combine::Max,
+    friction_combine: Combine::Min,
+};
+
+// the ball starts at pos going velocity, the player waits in the corner
+fn world(bounds: raylib::Vector2, pos: raylib::Vector2, velocity: raylib::Vector2, integrator: Integrator) -> World {
+    let ball = Ball { velocity, ..Ball::at(pos, 0.5, ELASTIC) };
+    let player = Player { pos: raylib::Vector2::new(0.0, bounds.y - 2.0), ..Player::new() };
+    let scene = Scene {
+        bounds,
+        integrator,
+        sleeping: false,
+        random_balls: 0,
+        balls: vec![ball],
+        player,
+        hoops: Vec::new(),
+        blocks: Vec::new(),
+        ..Scene::default()
+    };
+    World::new(SEED, scene)
+}
+
+// kinetic and potential, per kg, the floor is 0
+fn ball_energy(world: &World) -> f32 {
+    let ball = &world.balls[0];
+    let height = world.bounds - ball.pos;
+    energy(ball.velocity, ball.angular_velocity, ball.mass, ball.inertia()) + world.gravity.dot(height)
+}
+
+// the drift at the end and the worst one, in %
+fn drift(mut world: World) -> (f32, f32) {
+    let start = ball_energy(&world);
+    let mut worst: f32 = 0.0;
+    let mut last = 0.0;
+    for _ in 0..(SECONDS * world.timestep.hz) as u32 {
+        world.step(world.timestep.dt());
+        last = (ball_energy(&world) - start) / start * 100.0;
+        worst = if last.abs() > worst.abs() { last } else { worst };
+    }
+    (last, worst)
+}
+
+fn main() {
+    println!("{:>20} {:>12} {:>12} {:>12} {:>12}", "integrator", "flight", "worst", "bouncing", "worst");
+
+    for integrator in Integrator::ALL {
+        // thrown up and across a world too big to hit anything in
+        let bounds = raylib::Vector2::new_from(400.0);
+        let flight = world(bounds, raylib::Vector2::new(50.0, 100.0), raylib::Vector2::new(5.0, -40.0), integrator);
+        // dropped from the middle of the default world
+        let bounds = Scene::default().bounds;
+        let bouncing = world(bounds, bounds / 2.0, raylib::Vector2::zero(), integrator);
+
+        let (flight, flight_worst) = drift(flight);
+        let (bouncing, bouncing_worst) = drift(bouncing);
+        println!(
+            "{:>20} {:>12.4} {:>12.4} {:>12.4} {:>12.4}",
+            integrator.name(),
+            flight,
+            flight_worst,
+            bouncing,
+            bouncing_worst
+        );
+    }
+}
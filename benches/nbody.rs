@@ -0,0 +1,53 @@
+// cargo bench --features headless --bench nbody
+//
+// Times how long every ball takes to find the pull of all the others,
+// summing every pair against the Barnes–Hut tree, for a growing number of
+// balls. The tree sums every pair itself up to DIRECT_LIMIT balls. Error is
+// how far the tree is off, in % of the summed pull, averaged over every ball.
+
+use std::time::Instant;
+
+use aid::Rng;
+use physim::ball::Ball;
+use physim::nbody::{Gravitation, Quadtree};
+
+const SEED: u64 = 1234;
+
+// every pair, what the tree has to get close to
+fn direct(balls: &[Ball], gravitation: Gravitation) -> Vec<raylib::Vector2> {
+    let softening = gravitation.softening * gravitation.softening;
+    let pull = |i: usize| {
+        let mut total = raylib::Vector2::zero();
+        for (_, other) in balls.iter().enumerate().filter(|&(j, _)| j != i) {
+            let offset = other.pos - balls[i].pos;
+            let squared = offset.length_squared() + softening;
+            total += offset * (gravitation.constant * other.mass / (squared * squared.sqrt()));
+        }
+        total
+    };
+    (0..balls.len()).map(pull).collect()
+}
+
+fn main() {
+    println!("{:>8} {:>12} {:>12} {:>12}", "balls", "direct ms", "tree ms", "error %");
+
+    let gravitation = Gravitation::default();
+    for num_balls in [256, 1_000, 4_000, 16_000] {
+        // about four m² per ball, like the broadphase bench
+        let side = (num_balls as f32).sqrt() * 2.0;
+        let balls = Ball::new_vec(num_balls, raylib::Vector2::new_from(side), &mut Rng::new(SEED));
+
+        let start = Instant::now();
+        let exact = direct(&balls, gravitation);
+        let direct_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        let start = Instant::now();
+        let tree = Quadtree::new(&balls, gravitation);
+        let approx: Vec<raylib::Vector2> = balls.iter().enumerate().map(|(i, ball)| tree.acceleration(ball.pos, i)).collect();
+        let tree_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        let error: f32 = exact.iter().zip(approx.iter()).map(|(exact, approx)| (*approx - *exact).length() / exact.length()).sum();
+        let error = error / num_balls as f32 * 100.0;
+        println!("{:>8} {:>12.3} {:>12.3} {:>12.4}", num_balls, direct_ms, tree_ms, error);
+    }
+}
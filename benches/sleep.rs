@@ -0,0 +1,46 @@
+// cargo bench --features headless --bench sleep
+//
+// Times World::step on piles that have settled, with and without sleeping.
+// The asleep column shows how much of the pile came to rest.
+
+use std::time::Instant;
+
+use physim::scene::Scene;
+use physim::world::World;
+
+const SEED: u64 = 1234;
+const SETTLE_STEPS: u32 = 1_800;
+const STEPS: u32 = 120;
+
+// square world with room for about four balls per ball, see broadphase.rs
+fn world(num_balls: usize, sleeping: bool) -> World {
+    let side = (num_balls as f32).sqrt() * 2.0;
+    let scene = Scene {
+        bounds: raylib::Vector2::new_from(side),
+        random_balls: num_balls,
+        sleeping,
+        ..Scene::default()
+    };
+    World::new(SEED, scene)
+}
+
+fn main() {
+    println!("{:>8} {:>10} {:>10} {:>12}", "balls", "sleeping", "asleep", "ms / step");
+
+    for num_balls in [256, 1_000] {
+        for sleeping in [false, true] {
+            let mut world = world(num_balls, sleeping);
+            for _ in 0..SETTLE_STEPS {
+                world.step(world.timestep.dt());
+            }
+
+            let start = Instant::now();
+            for _ in 0..STEPS {
+                world.step(world.timestep.dt());
+            }
+            let ms = start.elapsed().as_secs_f64() * 1000.0 / STEPS as f64;
+
+            println!("{:>8} {:>10} {:>10} {:>12.3}", world.balls.len(), sleeping, world.asleep_count(), ms);
+        }
+    }
+}
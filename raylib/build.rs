@@ -1,17 +1,149 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use build_target::{Arch, Os};
+
 fn main() {
-    use std::path::Path;
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RAYLIB_SRC");
+
+    // the headless backend is plain Rust, there is nothing to link
+    if env::var_os("CARGO_FEATURE_HEADLESS").is_some() {
+        return;
+    }
+
     let arch = build_target::target_arch().unwrap();
+    let os = build_target::target_os().unwrap();
+    let dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+
+    if arch == Arch::WASM32 {
+        link_prebuilt(&dir.join("bin/WASM32"), "raylib.web");
+        return;
+    }
+    if arch == Arch::AARCH64 && os == Os::MacOs {
+        link_prebuilt(&dir.join("bin/AARCH64"), "raylib");
+        println!("cargo:rustc-flags=-l framework=CoreVideo -l framework=IOKit -l framework=Cocoa -l framework=GLUT -l framework=OpenGL");
+        return;
+    }
+
+    if link_pkg_config() {
+        return;
+    }
 
-    let mut bin_path = "";
-    if arch == build_target::Arch::WASM32 {
-        bin_path = "bin/WASM32"
-    } else if arch == build_target::Arch::AARCH64 {
-        bin_path = "bin/AARCH64"
+    let src = match env::var_os("RAYLIB_SRC") {
+        Some(src) => PathBuf::from(src),
+        None => dir.join("../external/raylib/src"),
+    };
+    if build_from_source(&src) {
+        link_system_libs(&os);
+        return;
     }
 
-    let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
-    println!(
-        "cargo:rustc-link-search=native={}",
-        Path::new(&dir).join(bin_path).display()
+    panic!(
+        "No raylib for {}-{}: install it so `pkg-config raylib` finds it, \
+         run `git submodule update --init` so it can be built from external/raylib, \
+         or build with `--features headless`",
+        arch.as_str(),
+        os.as_str()
     );
 }
+
+fn link_prebuilt(bin: &Path, lib: &str) {
+    println!("cargo:rustc-link-search=native={}", bin.display());
+    println!("cargo:rustc-link-lib=static={lib}");
+}
+
+fn link_pkg_config() -> bool {
+    let output = match Command::new("pkg-config").args(["--libs", "raylib"]).output() {
+        Ok(output) if output.status.success() => output,
+        _ => return false,
+    };
+
+    for flag in String::from_utf8_lossy(&output.stdout).split_whitespace() {
+        if let Some(path) = flag.strip_prefix("-L") {
+            println!("cargo:rustc-link-search=native={path}");
+        } else if let Some(lib) = flag.strip_prefix("-l") {
+            println!("cargo:rustc-link-lib={lib}");
+        }
+    }
+
+    true
+}
+
+// builds a copy in OUT_DIR so the objects make leaves behind don't end up
+// in the submodule
+fn build_from_source(src: &Path) -> bool {
+    if !src.join("Makefile").exists() {
+        return false;
+    }
+    // cargo looks at every file under a directory
+    println!("cargo:rerun-if-changed={}", src.display());
+    if let Some(head) = submodule_head(src) {
+        println!("cargo:rerun-if-changed={}", head.display());
+    }
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let build = out.join("raylib-src");
+    if build.exists() {
+        fs::remove_dir_all(&build).expect("Failed to clear the old raylib build");
+    }
+    copy_dir(src, &build).expect("Failed to copy the raylib sources");
+
+    let status = Command::new("make")
+        .arg("-C")
+        .arg(&build)
+        .arg("PLATFORM=PLATFORM_DESKTOP")
+        .arg(format!("RAYLIB_RELEASE_PATH={}", out.display()))
+        .status()
+        .expect("Failed to run make for raylib");
+    if !status.success() {
+        panic!("Failed to build raylib from {}", src.display());
+    }
+
+    link_prebuilt(&out, "raylib");
+    true
+}
+
+// the HEAD of the repository src is in, a submodule keeps its .git dir in
+// the parent's .git/modules and only a `gitdir:` file in its checkout
+fn submodule_head(src: &Path) -> Option<PathBuf> {
+    let git = src.ancestors().map(|dir| dir.join(".git")).find(|git| git.exists())?;
+    if git.is_dir() {
+        return Some(git.join("HEAD"));
+    }
+    let link = fs::read_to_string(&git).ok()?;
+    let gitdir = PathBuf::from(link.strip_prefix("gitdir:")?.trim());
+    Some(git.parent()?.join(gitdir).join("HEAD"))
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to.join(entry.file_name()))?;
+        } else {
+            fs::copy(entry.path(), to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+fn link_system_libs(os: &Os) {
+    match os {
+        Os::MacOs => println!("cargo:rustc-flags=-l framework=CoreVideo -l framework=IOKit -l framework=Cocoa -l framework=GLUT -l framework=OpenGL"),
+        Os::Windows => {
+            for lib in ["opengl32", "gdi32", "winmm", "user32", "shell32"] {
+                println!("cargo:rustc-link-lib={lib}");
+            }
+        }
+        _ => {
+            for lib in ["GL", "m", "pthread", "dl", "rt", "X11"] {
+                println!("cargo:rustc-link-lib={lib}");
+            }
+        }
+    }
+}
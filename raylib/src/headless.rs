@@ -0,0 +1,264 @@
+// Stand-in for the raylib C library when the `headless` feature is enabled.
+// Nothing is rendered, draw calls are recorded instead so they can be
+// inspected, and input can be simulated. Everything is per-thread.
+
+use std::cell::RefCell;
+use std::ffi::CStr;
+
+use crate::{Color, KeyboardKey, MouseButton, Rectangle, Vector2};
+
+const KEY_COUNT: usize = 512;
+const MOUSE_BUTTON_COUNT: usize = 7;
+pub const DEFAULT_FRAME_LIMIT: u64 = 600;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCall {
+    Clear(Color),
+    Line { start: Vector2, end: Vector2, thick: f32, color: Color },
+    Circle { center: Vector2, radius: f32, color: Color, filled: bool },
+    Rectangle { rec: Rectangle, color: Color, filled: bool },
+    // rotation is in degrees around origin, which is relative to rec.x/y
+    RotatedRectangle { rec: Rectangle, origin: Vector2, rotation: f32, color: Color },
+    Triangle { v1: Vector2, v2: Vector2, v3: Vector2, color: Color },
+    Text { text: String, pos_x: i32, pos_y: i32, font_size: i32, color: Color },
+}
+
+struct State {
+    screen_width: i32,
+    screen_height: i32,
+    resized: bool,
+    target_fps: i32,
+    frames_left: u64,
+    keys_down: [bool; KEY_COUNT],
+    keys_pressed: [bool; KEY_COUNT],
+    mouse_position: Vector2,
+    mouse_pressed: [bool; MOUSE_BUTTON_COUNT],
+    frame: Vec<DrawCall>,
+    last_frame: Vec<DrawCall>,
+    dropped_files: Vec<String>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State {
+        screen_width: 0,
+        screen_height: 0,
+        resized: false,
+        target_fps: 60,
+        frames_left: DEFAULT_FRAME_LIMIT,
+        keys_down: [false; KEY_COUNT],
+        keys_pressed: [false; KEY_COUNT],
+        mouse_position: Vector2::zero(),
+        mouse_pressed: [false; MOUSE_BUTTON_COUNT],
+        frame: Vec::new(),
+        last_frame: Vec::new(),
+        dropped_files: Vec::new(),
+    });
+}
+
+fn with<T>(f: impl FnOnce(&mut State) -> T) -> T {
+    STATE.with(|state| f(&mut state.borrow_mut()))
+}
+
+fn record(call: DrawCall) {
+    with(|state| state.frame.push(call));
+}
+
+unsafe fn to_string(text: *const i8) -> String {
+    CStr::from_ptr(text).to_string_lossy().into_owned()
+}
+
+// Simulation hooks, only exist in headless builds
+
+// how many frames until window_should_close() returns true
+pub fn set_frame_limit(frames: u64) {
+    with(|state| state.frames_left = frames);
+}
+
+// held keys stay down until released, presses only last for one frame
+pub fn set_key_down(key: KeyboardKey, down: bool) {
+    with(|state| {
+        let key = key as usize;
+        if down && !state.keys_down[key] {
+            state.keys_pressed[key] = true;
+        }
+        state.keys_down[key] = down;
+    });
+}
+
+pub fn press_key(key: KeyboardKey) {
+    with(|state| state.keys_pressed[key as usize] = true);
+}
+
+pub fn set_mouse_position(position: Vector2) {
+    with(|state| state.mouse_position = position);
+}
+
+pub fn press_mouse_button(button: MouseButton) {
+    with(|state| state.mouse_pressed[button as usize] = true);
+}
+
+pub fn resize_window(width: i32, height: i32) {
+    with(|state| {
+        state.screen_width = width;
+        state.screen_height = height;
+        state.resized = true;
+    });
+}
+
+pub fn drop_files(paths: &[&str]) {
+    with(|state| state.dropped_files.extend(paths.iter().map(|path| path.to_string())));
+}
+
+// everything drawn between the last begin_drawing() and end_drawing()
+pub fn take_draw_calls() -> Vec<DrawCall> {
+    with(|state| std::mem::take(&mut state.last_frame))
+}
+
+// rcore
+pub(crate) unsafe fn init_window_internal(width: i32, height: i32, _title: *const i8) {
+    with(|state| {
+        state.screen_width = width;
+        state.screen_height = height;
+    });
+}
+pub unsafe fn close_window() {}
+pub unsafe fn window_should_close() -> bool {
+    with(|state| state.frames_left == 0)
+}
+pub unsafe fn is_window_resized() -> bool {
+    with(|state| state.resized)
+}
+pub unsafe fn is_window_focused() -> bool {
+    true
+}
+pub unsafe fn set_window_position(_x: i32, _y: i32) {}
+pub unsafe fn set_window_size(width: i32, height: i32) {
+    with(|state| {
+        state.screen_width = width;
+        state.screen_height = height;
+    });
+}
+pub unsafe fn set_window_opacity(_opacity: f32) {}
+pub unsafe fn get_screen_width() -> i32 {
+    with(|state| state.screen_width)
+}
+pub unsafe fn get_screen_height() -> i32 {
+    with(|state| state.screen_height)
+}
+
+pub unsafe fn clear_background(color: Color) {
+    record(DrawCall::Clear(color));
+}
+pub unsafe fn begin_drawing() {
+    with(|state| state.frame.clear());
+}
+pub unsafe fn end_drawing() {
+    with(|state| {
+        state.last_frame = std::mem::take(&mut state.frame);
+        state.frames_left = state.frames_left.saturating_sub(1);
+        state.resized = false;
+        state.keys_pressed = [false; KEY_COUNT];
+        state.mouse_pressed = [false; MOUSE_BUTTON_COUNT];
+    });
+}
+
+// the clock always runs at exactly the target fps
+pub unsafe fn set_target_fps(fps: i32) {
+    with(|state| state.target_fps = fps.max(1));
+}
+pub unsafe fn get_frame_time() -> f32 {
+    with(|state| 1.0 / state.target_fps as f32)
+}
+pub unsafe fn get_fps() -> f64 {
+    with(|state| state.target_fps as f64)
+}
+
+pub unsafe fn set_config_flags(_flags: u32) {}
+
+pub unsafe fn is_file_dropped() -> bool {
+    with(|state| !state.dropped_files.is_empty())
+}
+
+// the C side hands out a list it owns, there's no need for that here
+pub unsafe fn take_dropped_files() -> Vec<String> {
+    with(|state| std::mem::take(&mut state.dropped_files))
+}
+
+pub unsafe fn is_key_pressed(key: KeyboardKey) -> bool {
+    with(|state| state.keys_pressed[key as usize])
+}
+pub unsafe fn is_key_down(key: KeyboardKey) -> bool {
+    with(|state| state.keys_down[key as usize])
+}
+
+pub unsafe fn get_mouse_position() -> Vector2 {
+    with(|state| state.mouse_position)
+}
+pub unsafe fn is_mouse_button_pressed(button: MouseButton) -> bool {
+    with(|state| state.mouse_pressed[button as usize])
+}
+
+// rshapes
+pub unsafe fn draw_line_v(start_pos: Vector2, end_pos: Vector2, color: Color) {
+    draw_line_ex(start_pos, end_pos, 1.0, color);
+}
+pub unsafe fn draw_line_ex(start: Vector2, end: Vector2, thick: f32, color: Color) {
+    record(DrawCall::Line { start, end, thick, color });
+}
+pub unsafe fn draw_circle(center_x: i32, center_y: i32, radius: f32, color: Color) {
+    draw_circle_v(Vector2::new(center_x as f32, center_y as f32), radius, color);
+}
+pub unsafe fn draw_circle_v(center: Vector2, radius: f32, color: Color) {
+    record(DrawCall::Circle { center, radius, color, filled: true });
+}
+pub unsafe fn draw_circle_lines(center_x: i32, center_y: i32, radius: f32, color: Color) {
+    draw_circle_lines_v(Vector2::new(center_x as f32, center_y as f32), radius, color);
+}
+pub unsafe fn draw_circle_lines_v(center: Vector2, radius: f32, color: Color) {
+    record(DrawCall::Circle { center, radius, color, filled: false });
+}
+pub unsafe fn draw_rectangle(pos_x: i32, pos_y: i32, width: i32, height: i32, color: Color) {
+    draw_rectangle_rec(Rectangle { x: pos_x as f32, y: pos_y as f32, width: width as f32, height: height as f32 }, color);
+}
+pub unsafe fn draw_rectangle_v(position: Vector2, size: Vector2, color: Color) {
+    draw_rectangle_rec(Rectangle { x: position.x, y: position.y, width: size.x, height: size.y }, color);
+}
+pub unsafe fn draw_rectangle_rec(rec: Rectangle, color: Color) {
+    record(DrawCall::Rectangle { rec, color, filled: true });
+}
+pub unsafe fn draw_rectangle_pro(rec: Rectangle, origin: Vector2, rotation: f32, color: Color) {
+    record(DrawCall::RotatedRectangle { rec, origin, rotation, color });
+}
+pub unsafe fn draw_rectangle_lines(pos_x: i32, pos_y: i32, width: i32, height: i32, color: Color) {
+    draw_rectangle_lines_ex(Rectangle { x: pos_x as f32, y: pos_y as f32, width: width as f32, height: height as f32 }, 1.0, color);
+}
+pub unsafe fn draw_rectangle_lines_ex(rec: Rectangle, _line_thick: f32, color: Color) {
+    record(DrawCall::Rectangle { rec, color, filled: false });
+}
+pub unsafe fn draw_triangle(v1: Vector2, v2: Vector2, v3: Vector2, color: Color) {
+    record(DrawCall::Triangle { v1, v2, v3, color });
+}
+
+// rtextures
+pub unsafe fn get_color(hex_value: u32) -> Color {
+    Color {
+        r: (hex_value >> 24) as u8,
+        g: (hex_value >> 16) as u8,
+        b: (hex_value >> 8) as u8,
+        a: hex_value as u8,
+    }
+}
+
+// rtext
+pub unsafe fn draw_fps(pos_x: i32, pos_y: i32) {
+    let fps = get_fps();
+    record(DrawCall::Text { text: format!("{fps} FPS"), pos_x, pos_y, font_size: 20, color: crate::LIME });
+}
+pub(crate) unsafe fn draw_text_internal(text: *const i8, pos_x: i32, pos_y: i32, font_size: i32, color: Color) {
+    record(DrawCall::Text { text: to_string(text), pos_x, pos_y, font_size, color });
+}
+
+// close enough to the default font for layout purposes
+pub(crate) unsafe fn measure_text_internal(text: *const i8, font_size: i32) -> i32 {
+    to_string(text).chars().count() as i32 * font_size / 2
+}
@@ -1,9 +1,14 @@
 #![allow(dead_code)]
 
-use std::ops::BitOr;
+#[cfg(feature = "headless")]
+mod headless;
+#[cfg(feature = "headless")]
+pub use headless::*;
+
+use std::ops::{Add, AddAssign, BitOr, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 #[repr(C)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -12,14 +17,14 @@ pub struct Color {
 }
 
 #[repr(C)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Vector2 {
     pub x: f32,
     pub y: f32
 }
 
 #[repr(C)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Rectangle {
     pub x: f32,
     pub y: f32,
@@ -27,73 +32,243 @@ pub struct Rectangle {
     pub height: f32
 }
 
+#[repr(C)]
+pub struct FilePathList {
+    pub capacity: u32,
+    pub count: u32,
+    pub paths: *mut *mut std::ffi::c_char,
+}
+
 impl Vector2 {
-    pub unsafe fn zero() -> Vector2 {
-        vector_2_zero()
+    pub const fn new(x: f32, y: f32) -> Vector2 {
+        Vector2{ x, y }
+    }
+    pub const fn zero() -> Vector2 {
+        Vector2{ x: 0.0, y: 0.0 }
     }
-    pub unsafe fn one() -> Vector2 {
-        vector_2_one()
+    pub const fn one() -> Vector2 {
+        Vector2{ x: 1.0, y: 1.0 }
     }
-    pub unsafe fn new_from(num: f32) -> Vector2 {
+    pub const fn new_from(num: f32) -> Vector2 {
         Vector2{ x: num, y: num }
     }
 
     pub fn pythagorean(&self) -> f32 {
-        ((self.x * self.x) + (self.y * self.y)).sqrt()
+        self.length_squared().sqrt()
+    }
+    pub fn length(self) -> f32 {
+        self.pythagorean()
+    }
+    pub fn length_squared(self) -> f32 {
+        (self.x * self.x) + (self.y * self.y)
+    }
+    pub fn distance(self, other: Vector2) -> f32 {
+        (other - self).pythagorean()
+    }
+    pub fn distance_squared(self, other: Vector2) -> f32 {
+        (other - self).length_squared()
     }
     pub fn add(self, vec: Vector2) -> Vector2 {
-        unsafe { vector_2_add(self, vec) }
+        self + vec
     }
     pub fn addeq(&mut self, vec: Vector2) {
-        unsafe { *self = vector_2_add(*self, vec); }
+        *self += vec;
     }
     pub fn add_value(self, value: f32) -> Vector2 {
-        unsafe { vector_2_add_value(self, value) }
+        Vector2{ x: self.x + value, y: self.y + value }
     }
     pub fn sub(self, vec: Vector2) -> Vector2 {
-        unsafe { vector_2_subtract(self, vec) }
+        self - vec
     }
     pub fn subeq(&mut self, vec: Vector2) {
-        unsafe { *self = vector_2_subtract(*self, vec); }
+        *self -= vec;
     }
     pub fn sub_value(self, value: f32) -> Vector2 {
-        unsafe { vector_2_subtract_value(self, value) }
+        Vector2{ x: self.x - value, y: self.y - value }
     }
     pub fn subeq_value(&mut self, value: f32) {
-         unsafe { *self = vector_2_subtract_value(*self, value) }
+        *self = self.sub_value(value);
     }
     pub fn mult(self, vec: Vector2) -> Vector2 {
-        unsafe { vector_2_multiply(self, vec) }
+        self * vec
     }
     pub fn multeq(&mut self, vec: Vector2) {
-        unsafe { *self = vector_2_multiply(*self, vec); }
+        *self = *self * vec;
     }
     pub fn mult_value(self, value: f32) -> Vector2 {
-        Vector2{ x: self.x * value, y: self.y * value }
+        self * value
     }
     pub fn multeq_value(&mut self, value: f32) {
-         (*self).x *= value;
-         (*self).y *= value;
+        *self *= value;
     }
     pub fn div(self, vec: Vector2) -> Vector2 {
-        unsafe { vector_2_divide(self, vec) }
+        self / vec
     }
     pub fn diveq(&mut self, vec: Vector2) {
-        unsafe { *self = vector_2_divide(*self, vec); }
+        *self = *self / vec;
     }
     pub fn div_value(self, value: f32) -> Vector2 {
-        Vector2{ x: self.x / value, y: self.y / value }
+        self / value
     }
     pub fn diveq_value(&mut self, value: f32) {
-         (*self).x /= value;
-         (*self).y /= value;
+        *self /= value;
     }
+    // zero stays zero instead of turning into NaN
     pub fn normalize(self) -> Vector2 {
-        unsafe { vector_2_normalize(self) }
+        let length = self.pythagorean();
+        if length > 0.0 {
+            return self / length;
+        }
+        self
     }
     pub fn dot(self, other: Vector2) -> f32 {
-        unsafe { vector_2_dot_product(self, other) }
+        (self.x * other.x) + (self.y * other.y)
+    }
+    // z of the 3D cross product
+    pub fn cross(self, other: Vector2) -> f32 {
+        (self.x * other.y) - (self.y * other.x)
     }
+    pub fn lerp(self, other: Vector2, amount: f32) -> Vector2 {
+        self + (other - self) * amount
+    }
+    // angle in radians, counter clockwise in a y-up frame
+    pub fn rotate(self, angle: f32) -> Vector2 {
+        let (sin, cos) = angle.sin_cos();
+        Vector2{ x: self.x * cos - self.y * sin, y: self.x * sin + self.y * cos }
+    }
+    // rotated by 90 degrees
+    pub fn perp(self) -> Vector2 {
+        Vector2{ x: -self.y, y: self.x }
+    }
+    // normal has to be normalized
+    pub fn reflect(self, normal: Vector2) -> Vector2 {
+        self - normal * (2.0 * self.dot(normal))
+    }
+    // angle from self to other in radians
+    pub fn angle(self, other: Vector2) -> f32 {
+        self.cross(other).atan2(self.dot(other))
+    }
+    pub fn clamp_length(self, min: f32, max: f32) -> Vector2 {
+        let length_squared = self.length_squared();
+        if length_squared > 0.0 {
+            let length = length_squared.sqrt();
+            if length < min {
+                return self * (min / length);
+            }
+            if length > max {
+                return self * (max / length);
+            }
+        }
+        self
+    }
+}
+
+impl Add for Vector2 {
+    type Output = Vector2;
+
+    fn add(self, rhs: Vector2) -> Vector2 { Vector2{ x: self.x + rhs.x, y: self.y + rhs.y } }
+}
+
+impl Sub for Vector2 {
+    type Output = Vector2;
+
+    fn sub(self, rhs: Vector2) -> Vector2 { Vector2{ x: self.x - rhs.x, y: self.y - rhs.y } }
+}
+
+impl Mul for Vector2 {
+    type Output = Vector2;
+
+    fn mul(self, rhs: Vector2) -> Vector2 { Vector2{ x: self.x * rhs.x, y: self.y * rhs.y } }
+}
+
+impl Mul<f32> for Vector2 {
+    type Output = Vector2;
+
+    fn mul(self, rhs: f32) -> Vector2 { Vector2{ x: self.x * rhs, y: self.y * rhs } }
+}
+
+impl Mul<Vector2> for f32 {
+    type Output = Vector2;
+
+    fn mul(self, rhs: Vector2) -> Vector2 { rhs * self }
+}
+
+impl Div for Vector2 {
+    type Output = Vector2;
+
+    fn div(self, rhs: Vector2) -> Vector2 { Vector2{ x: self.x / rhs.x, y: self.y / rhs.y } }
+}
+
+impl Div<f32> for Vector2 {
+    type Output = Vector2;
+
+    fn div(self, rhs: f32) -> Vector2 { Vector2{ x: self.x / rhs, y: self.y / rhs } }
+}
+
+impl Neg for Vector2 {
+    type Output = Vector2;
+
+    fn neg(self) -> Vector2 { Vector2{ x: -self.x, y: -self.y } }
+}
+
+impl AddAssign for Vector2 {
+    fn add_assign(&mut self, rhs: Vector2) { *self = *self + rhs; }
+}
+
+impl SubAssign for Vector2 {
+    fn sub_assign(&mut self, rhs: Vector2) { *self = *self - rhs; }
+}
+
+impl MulAssign<f32> for Vector2 {
+    fn mul_assign(&mut self, rhs: f32) { *self = *self * rhs; }
+}
+
+impl DivAssign<f32> for Vector2 {
+    fn div_assign(&mut self, rhs: f32) { *self = *self / rhs; }
+}
+
+// rmath
+pub fn float_equals(x: f32, y: f32) -> bool {
+    const EPSILON: f32 = 0.000001;
+    (x - y).abs() <= EPSILON * 1.0f32.max(x.abs().max(y.abs()))
+}
+
+pub fn vector_2_zero() -> Vector2 {
+    Vector2::zero()
+}
+
+pub fn vector_2_one() -> Vector2 {
+    Vector2::one()
+}
+
+// rshapes collision detection, plain math so it doesn't need to go through C
+pub fn check_collision_circles(center1: Vector2, radius1: f32, center2: Vector2, radius2: f32) -> bool {
+    let radius = radius1 + radius2;
+    center1.distance_squared(center2) <= radius * radius
+}
+
+pub fn check_collision_circle_rec(center: Vector2, radius: f32, rec: Rectangle) -> bool {
+    let closest = Vector2{
+        x: center.x.clamp(rec.x, rec.x + rec.width),
+        y: center.y.clamp(rec.y, rec.y + rec.height),
+    };
+    center.distance_squared(closest) <= radius * radius
+}
+
+pub fn check_collision_circle_line(center: Vector2, radius: f32, p1: Vector2, p2: Vector2) -> bool {
+    let line = p2 - p1;
+    let length_squared = line.length_squared();
+    let t = if length_squared > 0.0 { ((center - p1).dot(line) / length_squared).clamp(0.0, 1.0) } else { 0.0 };
+    center.distance_squared(p1 + line * t) <= radius * radius
+}
+
+pub fn check_collision_point_rec(point: Vector2, rec: Rectangle) -> bool {
+    point.x >= rec.x && point.x < rec.x + rec.width && point.y >= rec.y && point.y < rec.y + rec.height
+}
+
+pub fn check_collision_recs(rec1: Rectangle, rec2: Rectangle) -> bool {
+    rec1.x < rec2.x + rec2.width && rec1.x + rec1.width > rec2.x &&
+        rec1.y < rec2.y + rec2.height && rec1.y + rec1.height > rec2.y
 }
 
 
@@ -106,6 +281,21 @@ pub unsafe fn get_screen_dimensions() -> Vector2 {
 }
 
 
+// paths of everything dropped onto the window since the last call
+#[cfg(not(feature = "headless"))]
+pub unsafe fn take_dropped_files() -> Vec<String> {
+    if !is_file_dropped() {
+        return Vec::new();
+    }
+
+    let list = load_dropped_files();
+    let paths = (0..list.count as usize)
+        .map(|i| std::ffi::CStr::from_ptr(*list.paths.add(i)).to_string_lossy().into_owned())
+        .collect();
+    unload_dropped_files(list);
+    paths
+}
+
 pub unsafe fn draw_text(text: &str, pos_x: i32, pos_y: i32, font_size: i32, color: Color) {
     draw_text_internal((text.to_string() + "\0").as_ptr() as *const i8, pos_x, pos_y, font_size, color);
 }
@@ -114,6 +304,7 @@ pub unsafe fn measure_text(text: &str, font_size: i32) -> i32 {
     measure_text_internal((text.to_string() + "\0").as_ptr() as *const i8, font_size)
 }
 
+#[cfg(not(feature = "headless"))]
 extern "C" {
 // rcore
     // Window-related functions
@@ -129,6 +320,8 @@ extern "C" {
     pub fn is_window_focused() -> bool;
     #[link_name="SetWindowPosition"]
     pub fn set_window_position(x: i32, y: i32);
+    #[link_name="SetWindowSize"]
+    pub fn set_window_size(width: i32, height: i32);
     #[link_name="SetWindowOpacity"]
     pub fn set_window_opacity(opacity: f32);
     #[link_name="GetScreenWidth"]
@@ -156,6 +349,14 @@ extern "C" {
     #[link_name="SetConfigFlags"]
     pub fn set_config_flags(flags: u32);
 
+    // File system functions
+    #[link_name="IsFileDropped"]
+    pub fn is_file_dropped() -> bool;
+    #[link_name="LoadDroppedFiles"]
+    pub fn load_dropped_files() -> FilePathList;
+    #[link_name="UnloadDroppedFiles"]
+    pub fn unload_dropped_files(files: FilePathList);
+
     // Input-related functions: keyboard
     #[link_name="IsKeyPressed"]
     pub fn is_key_pressed(key: KeyboardKey) -> bool;
@@ -170,6 +371,10 @@ extern "C" {
 
 // rshapes
     // Basic shapes drawing functions
+    #[link_name="DrawLineV"]
+    pub fn draw_line_v(start_pos: Vector2, end_pos: Vector2, color: Color);
+    #[link_name="DrawLineEx"]
+    pub fn draw_line_ex(start_pos: Vector2, end_pos: Vector2, thick: f32, color: Color);
     #[link_name="DrawCircle"]
     pub fn draw_circle(center_x: i32, center_y: i32, radius: f32, color: Color);
     #[link_name="DrawCircleV"]
@@ -184,20 +389,15 @@ extern "C" {
     pub fn draw_rectangle_v(position: Vector2, size: Vector2, color: Color);
     #[link_name="DrawRectangleRec"]
     pub fn draw_rectangle_rec(rec: Rectangle, color: Color);
+    #[link_name="DrawRectanglePro"]
+    pub fn draw_rectangle_pro(rec: Rectangle, origin: Vector2, rotation: f32, color: Color);
     #[link_name="DrawRectangleLines"]
     pub fn draw_rectangle_lines(pos_x: i32, pos_y: i32, width: i32, height: i32, color: Color);
     #[link_name="DrawRectangleLinesEx"]
     pub fn draw_rectangle_lines_ex(rec: Rectangle, line_thick: f32, color: Color);
-
-    // Basic shapes collision detection functions
-    #[link_name="CheckCollisionCircles"]
-    pub fn check_collision_circles(center1: Vector2, radius1: f32, center2: Vector2, radius2: f32) -> bool;
-    #[link_name="CheckCollisionCircleRec"]
-    pub fn check_collision_circle_rec(center: Vector2, radius: f32, rec: Rectangle) -> bool;
-    #[link_name="CheckCollisionCircleLine"]
-    pub fn check_collision_circle_line(center: Vector2, radius: f32, p1: Vector2, p2: Vector2);
-    #[link_name="CheckCollisionPointRec"]
-    pub fn check_collision_point_rec(point: Vector2, rec: Rectangle) -> bool;
+    // vertices counter clockwise on screen
+    #[link_name="DrawTriangle"]
+    pub fn draw_triangle(v1: Vector2, v2: Vector2, v3: Vector2, color: Color);
 
 // rtextures
     // Color/pixel related functions
@@ -218,33 +418,6 @@ extern "C" {
     // Text strings management functions (no UTF-8 strings, only byte chars)
     #[link_name="TextFormat"]
     pub fn text_format(text: *const i8, ...) -> *const i8;
-
-// rmath
-    // Utils math
-    #[link_name="FloatEquals"]
-    pub fn float_equals(x: f32, y: f32) -> i32;
-
-    // Vector2 math
-    #[link_name="Vector2Zero"]
-    pub fn vector_2_zero() -> Vector2;
-    #[link_name="Vector2One"]
-        pub fn vector_2_one() -> Vector2;
-    #[link_name="Vector2Add"]
-    pub fn vector_2_add(v1: Vector2, v2: Vector2) -> Vector2;
-    #[link_name="Vector2AddValue"]
-    pub fn vector_2_add_value(v1: Vector2, add: f32) -> Vector2;
-    #[link_name="Vector2Subtract"]
-    pub fn vector_2_subtract(v1: Vector2, v2: Vector2) -> Vector2;
-    #[link_name="Vector2SubtractValue"]
-    pub fn vector_2_subtract_value(v1: Vector2, sub: f32) -> Vector2;
-    #[link_name="Vector2DotProduct"]
-    pub fn vector_2_dot_product(v1: Vector2, v2: Vector2) -> f32;
-    #[link_name="Vector2Multiply"]
-    pub fn vector_2_multiply(v1: Vector2, v2: Vector2) -> Vector2;
-    #[link_name="Vector2Normalize"]
-    pub fn vector_2_normalize(v: Vector2) -> Vector2;
-    #[link_name="Vector2Divide"]
-    pub fn vector_2_divide(v1: Vector2, v2: Vector2) -> Vector2;
 }
 
 #[repr(u32)]
@@ -1,8 +1,14 @@
-use libc;
-use raylib::{self, RAYWHITE};
-use std::{cmp, usize};
-
-use crate::obj::{Collision, PhyObj};
+use aid::Rng;
+use raylib;
+
+use crate::ccd::{stop_at, sweep_circle_polygon, SWEEP_THRESHOLD};
+use crate::collider::Collider;
+use crate::contact::{circle_circle, circle_convex, circle_rect, inverse, Manifold};
+use crate::field::Forces;
+use crate::integrator::Integrator;
+use crate::material::Material;
+use crate::obj::{Collision, PhyObj, RectBody};
+use crate::sleep::Sleep;
 
 const COLORS: [raylib::Color; 4] = [
     raylib::PINK,
@@ -19,165 +25,161 @@ pub const GRAVITY_CONSTANT: f32 = 9.81;
 #[derive(Clone, Copy)]
 pub struct Ball {
     pub pos: raylib::Vector2,      // in m
+    pub prev_pos: raylib::Vector2, // in m, pos at the start of the last step
     pub velocity: raylib::Vector2, // in m/s
+    pub drift: raylib::Vector2,    // in m, see Integrator::step
+    pub angle: f32,                // in rad, clockwise on screen
+    pub prev_angle: f32,           // in rad, angle at the start of the last step
+    pub angular_velocity: f32,     // in rad/s
     pub radius: f32,               // in m
-    pub elast: f32,                // in percent
     pub mass: f32,                 // in kg
+    pub material: Material,
     pub color: raylib::Color,
+    pub bullet: bool, // always swept, not only when it's fast
+    pub sleep: Sleep,
 }
 
 impl Collision for Ball {
-    fn collision_with_ball(&mut self, ball: &mut Ball) {
-        unsafe {
-            if !raylib::check_collision_circles(
-                self.pos,
-            self.radius,
-            ball.pos,
-            ball.radius,
-            ) {
-                return;
-            }
-        }
-
-        let m_1 = self.mass;
-        let v_1 = self.velocity;
-        let p_1 = self.pos;
-        let r_1 = self.radius;
-
-        let m_2 = ball.mass;
-        let v_2 = ball.velocity;
-        let p_2 = ball.pos;
-        let r_2 = ball.radius;
-
-        let elast = (self.elast + ball.elast) / 2.0;
-        let delta = p_2.sub(p_1);
-        let dist = delta.pythagorean();
-        let min_dist = r_1 + r_2;
-
-        if !(dist < min_dist && dist > 0.0) {
-            return;
-        }
-
-        let normal = delta.div_value(dist);
-
-        let penetration = min_dist - dist;
-        let correction = normal.mult_value(penetration / 2.0);
-        self.pos.subeq(correction);
-        ball.pos.addeq(correction);
-
-        let rv = v_2.sub(v_1);
-        let vel_along_normal = rv.dot(normal);
-
-        if vel_along_normal > 0.0 {
-            return;
-        }
-
-        let impulse_scalar = -(1.0 + elast) * vel_along_normal / (1.0 / m_1 + 1.0 / m_2);
-        let impulse = normal.mult_value(impulse_scalar);
-
-        self.velocity = v_1.sub(impulse.div_value(m_1));
-        ball.velocity = v_2.add(impulse.div_value(m_2));
+    fn contact_with_ball(&self, ball: &Ball) -> Option<Manifold> {
+        circle_circle(self, ball)
     }
 
-    fn collision_with_rect<T>(_rect: &mut T) {
-        todo!()
+    fn contact_with_rect<T: RectBody>(&self, rect: &T) -> Option<Manifold> {
+        circle_rect(self, rect.rect()).map(Manifold::flipped)
     }
 }
 
 impl Ball {
-    pub unsafe fn new(screen: raylib::Vector2) -> Self {
-        let width: i32 = (screen.x / PIXELS_PER_METER).ceil() as i32;
-        let height: i32 = (screen.y / PIXELS_PER_METER).ceil() as i32;
+    // somewhere inside bounds (in m)
+    pub fn new(bounds: raylib::Vector2, rng: &mut Rng) -> Self {
+        let width = bounds.x.ceil() as u32;
+        let height = bounds.y.ceil() as u32;
         let mut ball = Ball {
             pos: raylib::Vector2 {
-                x: (libc::rand() % width) as f32,
-                y: (libc::rand() % height) as f32,
+                x: rng.below(width) as f32,
+                y: rng.below(height) as f32,
             },
+            prev_pos: raylib::Vector2::zero(),
             velocity: raylib::Vector2 {
-                x: cmp::max(libc::rand() % 16, 2) as f32,
+                x: rng.below(16).max(2) as f32,
                 y: 0.0,
             },
+            drift: raylib::Vector2::zero(),
+            angle: 0.0,
+            prev_angle: 0.0,
+            angular_velocity: 0.0,
             radius: 0.5,
-            elast: cmp::max(libc::rand() % 100, 92) as f32 / 100.0 - 0.01,
-            mass: 0.2,
-            color: COLORS[(libc::rand() % 4) as usize],
+            mass: 0.0,
+            material: Material::RUBBER,
+            color: COLORS[rng.below(COLORS.len() as u32) as usize],
+            bullet: false,
+            sleep: Sleep::default(),
         };
-        if libc::rand() % 2 == 0 {
+        if rng.bool() {
             ball.velocity.x *= -1.0;
         }
+        ball.mass = ball.mass_from_density();
+        ball.prev_pos = ball.pos;
 
-        return ball;
+        ball
     }
 
-    pub unsafe fn new_vec(num: usize, screen: raylib::Vector2) -> Vec<Ball> {
-        (0..num).map(|_| Ball::new(screen)).collect()
+    // resting at pos (in m), the mass comes from the material's density
+    pub fn at(pos: raylib::Vector2, radius: f32, material: Material) -> Self {
+        let mut ball = Ball {
+            pos,
+            prev_pos: pos,
+            velocity: raylib::Vector2::zero(),
+            drift: raylib::Vector2::zero(),
+            angle: 0.0,
+            prev_angle: 0.0,
+            angular_velocity: 0.0,
+            radius,
+            mass: 0.0,
+            material,
+            color: raylib::PINK,
+            bullet: false,
+            sleep: Sleep::default(),
+        };
+        ball.mass = ball.mass_from_density();
+        ball
     }
 
-    pub unsafe fn draw(&self, index: usize) {
-        raylib::draw_circle_v(
-            self.pos.mult_value(PIXELS_PER_METER),
-            self.radius * PIXELS_PER_METER,
-            self.color,
-        );
-        let txt = &format!("{index}");
-        raylib::draw_text(txt, (self.pos.x * PIXELS_PER_METER - raylib::measure_text(txt, 5) as f32 / 2.0) as i32, (self.pos.y * PIXELS_PER_METER - 5.0) as i32, 5, raylib::BLACK);
+    pub fn new_vec(num: usize, bounds: raylib::Vector2, rng: &mut Rng) -> Vec<Ball> {
+        (0..num).map(|_| Ball::new(bounds, rng)).collect()
+    }
+
+    // in kg, what the material weighs over the area of the ball
+    pub fn mass_from_density(&self) -> f32 {
+        self.material.density * std::f32::consts::PI * self.radius * self.radius
     }
 
+    pub fn set_material(&mut self, material: Material) {
+        self.material = material;
+        self.mass = self.mass_from_density();
+    }
 
-}
+    // solid disc, in kg·m²
+    pub fn inertia(&self) -> f32 {
+        0.5 * self.mass * self.radius * self.radius
+    }
 
-pub unsafe fn update_ball_to_ball_collision(index: usize, balls: &mut Vec<Ball>) {
-    let num = balls.len();
+    pub fn inv_inertia(&self) -> f32 {
+        inverse(self.inertia())
+    }
 
-    //  offset by +1 so left.last() == left[index]
-    let (left, right) = balls.split_at_mut(index + 1);
+    // whether moving by displacement in one step could skip past something
+    pub fn needs_sweep(&self, displacement: raylib::Vector2) -> bool {
+        self.bullet || displacement.length() > self.radius * SWEEP_THRESHOLD
+    }
+
+    // alpha blends between prev_pos and pos, see FixedTimestep::alpha
+    pub unsafe fn draw(&self, index: usize, alpha: f32) {
+        let pos = self.prev_pos.lerp(self.pos, alpha);
+        let angle = self.prev_angle + (self.angle - self.prev_angle) * alpha;
+        let center = pos.mult_value(PIXELS_PER_METER);
+        let radius = self.radius * PIXELS_PER_METER;
+        raylib::draw_circle_v(center, radius, self.color);
 
-    //  balls[index] == left.last() && balls[index + 1] == right.first()
-    //  checks the left.last() against all of right
-    for i in (index + 1)..num {
-        left[index].collision_with_ball(&mut right[i - index - 1]);
+        // marker from the center to the rim so spin is visible
+        let rim = center + raylib::Vector2::new(radius, 0.0).rotate(angle);
+        raylib::draw_line_v(center, rim, raylib::BLACK);
+
+        let txt = &format!("{index}");
+        raylib::draw_text(txt, (pos.x * PIXELS_PER_METER - raylib::measure_text(txt, 5) as f32 / 2.0) as i32, (pos.y * PIXELS_PER_METER - 5.0) as i32, 5, raylib::BLACK);
     }
 }
 
 impl PhyObj for Ball {
-    unsafe fn update_gravity(&mut self, dt: f32) {
-        if raylib::float_equals(self.velocity.x, 0.0) != 0 {
-            self.velocity.x = 0.0;
-        }
-        if raylib::float_equals(self.velocity.y, 0.0) != 0 {
-            self.velocity.y = 0.0;
-        }
-
-        self.velocity.y += GRAVITY_CONSTANT * dt;
+    fn integrate(&mut self, integrator: Integrator, forces: &Forces, dt: f32) {
+        let (mass, radius) = (self.mass, self.radius);
+        let acceleration = |pos, velocity| forces.on_ball(pos, velocity, radius, mass) / mass;
+        (self.velocity, self.drift) = integrator.step(self.pos, self.velocity, dt, acceleration);
     }
 
-    unsafe fn update_clamp(&mut self, screen: raylib::Vector2) {
-        let coords = screen.div_value(PIXELS_PER_METER);
-
-        if self.pos.y >= coords.y - self.radius || self.pos.y <= self.radius {
-            self.pos.y = self.pos.y.clamp(self.radius, coords.y - self.radius);
-            self.velocity.y *= -self.elast;
-        }
-        if self.pos.x <= self.radius || self.pos.x >= coords.x - self.radius {
-            self.pos.x = self.pos.x.clamp(self.radius, coords.x - self.radius);
-            self.velocity.x *= -self.elast;
-        }
+    fn contact_with_collider(&self, collider: &Collider) -> Option<Manifold> {
+        circle_convex(self, &collider.points)
     }
-    unsafe fn update(&mut self, screen: raylib::Vector2, dt: f32) {
-        self.update_gravity(dt);
-        self.update_clamp(screen);
-
-        if NUM_OF_BALLS == 1 {
-            println!(
-                "{}, {}; {}, {}",
-                self.velocity.x, self.velocity.y, self.pos.x, self.pos.y
-            );
-        }
 
-        self.pos.addeq(self.velocity.mult_value(dt));
+    fn update_position(&mut self, colliders: &[Collider], dt: f32) {
+        // stops at the first collider in the way
+        let displacement = self.velocity * dt + std::mem::take(&mut self.drift);
+        let hit = if self.needs_sweep(displacement) {
+            colliders
+                .iter()
+                .filter_map(|collider| sweep_circle_polygon(self.pos, self.radius, displacement, &collider.points))
+                .min_by(f32::total_cmp)
+        } else {
+            None
+        };
+        self.pos = match hit {
+            Some(t) => stop_at(self.pos, displacement, t),
+            None => self.pos + displacement,
+        };
+        self.angle += self.angular_velocity * dt;
     }
 
-    unsafe fn update_movement(&mut self, _dt: f32) {
+    fn update_movement(&mut self, _dt: f32) {
         unimplemented!()
     }
 }
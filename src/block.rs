@@ -0,0 +1,119 @@
+use crate::ball::{Ball, PIXELS_PER_METER};
+use crate::collider::Collider;
+use crate::contact::{circle_rect, rect_collider, rect_rect, Manifold};
+use crate::field::Forces;
+use crate::integrator::Integrator;
+use crate::material::Material;
+use crate::obj::{Collision, PhyObj, RectBody};
+use crate::sleep::Sleep;
+
+// Axis aligned box. With an infinite mass it never moves and works as a
+// wall or a platform.
+#[derive(Clone, Copy)]
+pub struct Block {
+    pub pos: raylib::Vector2,      // in m
+    pub prev_pos: raylib::Vector2, // in m, pos at the start of the last step
+    pub dim: raylib::Vector2,      // in m
+    pub velocity: raylib::Vector2, // in m/s
+    pub drift: raylib::Vector2,    // in m, see Integrator::step
+    pub mass: f32,                 // in kg
+    pub material: Material,
+    pub color: raylib::Color,
+    pub sleep: Sleep,
+}
+
+impl Block {
+    pub fn new(pos: raylib::Vector2, dim: raylib::Vector2, mass: f32) -> Self {
+        Block {
+            pos,
+            prev_pos: pos,
+            dim,
+            velocity: raylib::Vector2::zero(),
+            drift: raylib::Vector2::zero(),
+            mass,
+            material: Material::WOOD,
+            color: raylib::BROWN,
+            sleep: Sleep::default(),
+        }
+    }
+
+    pub fn new_static(pos: raylib::Vector2, dim: raylib::Vector2) -> Self {
+        Block {
+            color: raylib::DARKBROWN,
+            ..Block::new(pos, dim, f32::INFINITY)
+        }
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.mass.is_infinite()
+    }
+
+    pub unsafe fn draw(&self, alpha: f32) {
+        let pos = self.prev_pos.lerp(self.pos, alpha);
+        raylib::draw_rectangle_v(
+            pos * PIXELS_PER_METER,
+            self.dim * PIXELS_PER_METER,
+            self.color,
+        );
+    }
+}
+
+impl RectBody for Block {
+    fn rect(&self) -> raylib::Rectangle {
+        raylib::Rectangle {
+            x: self.pos.x,
+            y: self.pos.y,
+            width: self.dim.x,
+            height: self.dim.y,
+        }
+    }
+
+    fn velocity(&self) -> raylib::Vector2 {
+        self.velocity
+    }
+
+    fn inv_mass(&self) -> f32 {
+        if self.is_static() {
+            return 0.0;
+        }
+        1.0 / self.mass
+    }
+
+    fn material(&self) -> Material {
+        self.material
+    }
+}
+
+impl Collision for Block {
+    fn contact_with_ball(&self, ball: &Ball) -> Option<Manifold> {
+        circle_rect(ball, self.rect())
+    }
+
+    fn contact_with_rect<T: RectBody>(&self, rect: &T) -> Option<Manifold> {
+        rect_rect(self.rect(), rect.rect())
+    }
+}
+
+impl PhyObj for Block {
+    fn integrate(&mut self, integrator: Integrator, forces: &Forces, dt: f32) {
+        if self.is_static() {
+            return;
+        }
+        let mass = self.mass;
+        let center = self.pos + self.dim / 2.0;
+        (self.velocity, self.drift) = integrator.step(center, self.velocity, dt, |pos, _| forces.on(pos, mass) / mass);
+    }
+
+    fn update_movement(&mut self, _dt: f32) {}
+
+    fn contact_with_collider(&self, collider: &Collider) -> Option<Manifold> {
+        rect_collider(self.rect(), collider)
+    }
+
+    fn update_position(&mut self, _colliders: &[Collider], dt: f32) {
+        if self.is_static() {
+            return;
+        }
+        self.pos += self.velocity * dt + std::mem::take(&mut self.drift);
+    }
+}
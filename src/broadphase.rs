@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use crate::ball::Ball;
+
+// How candidate pairs are found before the exact circle test in
+// Ball::contact_with_ball runs on them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BroadphaseKind {
+    SpatialHash,
+    SweepAndPrune,
+    BruteForce,
+}
+
+impl BroadphaseKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            BroadphaseKind::SpatialHash => "spatial hash",
+            BroadphaseKind::SweepAndPrune => "sweep and prune",
+            BroadphaseKind::BruteForce => "brute force",
+        }
+    }
+
+    pub fn next(self) -> BroadphaseKind {
+        match self {
+            BroadphaseKind::SpatialHash => BroadphaseKind::SweepAndPrune,
+            BroadphaseKind::SweepAndPrune => BroadphaseKind::BruteForce,
+            BroadphaseKind::BruteForce => BroadphaseKind::SpatialHash,
+        }
+    }
+}
+
+// Keeps its buffers between steps so a step doesn't allocate.
+pub struct Broadphase {
+    pub kind: BroadphaseKind,
+    pairs: Vec<(usize, usize)>,
+    keys: Vec<(u64, usize)>,             // (cell, ball), sorted by cell
+    cells: HashMap<u64, (usize, usize)>, // cell -> range in keys
+    order: Vec<usize>,                   // balls sorted by their left edge
+}
+
+impl Broadphase {
+    pub fn new(kind: BroadphaseKind) -> Self {
+        Broadphase {
+            kind,
+            pairs: Vec::new(),
+            keys: Vec::new(),
+            cells: HashMap::new(),
+            order: Vec::new(),
+        }
+    }
+
+    // Every pair of balls whose bounding boxes overlap, each pair once as
+    // (lower index, higher index) and sorted, so the order collisions get
+    // solved in doesn't depend on the broadphase.
+    pub fn find_pairs(&mut self, balls: &[Ball]) -> &[(usize, usize)] {
+        self.find_pairs_within(balls, 0.0)
+    }
+
+    // same, with the boxes grown by margin (in m) on every side, for balls
+    // that reach further than they are big, see liquid.rs
+    pub fn find_pairs_within(&mut self, balls: &[Ball], margin: f32) -> &[(usize, usize)] {
+        self.pairs.clear();
+
+        match self.kind {
+            BroadphaseKind::SpatialHash => self.spatial_hash(balls, margin),
+            BroadphaseKind::SweepAndPrune => self.sweep_and_prune(balls, margin),
+            BroadphaseKind::BruteForce => self.brute_force(balls, margin),
+        }
+
+        self.pairs.sort_unstable();
+        &self.pairs
+    }
+
+    // Uniform grid with cells as big as the biggest ball. A ball only goes
+    // into the cell its center is in, so anything touching it has its center
+    // in one of the 3x3 cells around it.
+    fn spatial_hash(&mut self, balls: &[Ball], margin: f32) {
+        let max_radius = balls.iter().fold(0.0f32, |max, ball| max.max(ball.radius));
+        let cell_size = max_radius * 2.0 + margin * 2.0;
+        if cell_size <= 0.0 {
+            return self.brute_force(balls, margin);
+        }
+
+        self.keys.clear();
+        for (i, ball) in balls.iter().enumerate() {
+            let (x, y) = cell_of(ball.pos, cell_size);
+            self.keys.push((cell_key(x, y), i));
+        }
+        self.keys.sort_unstable();
+
+        self.cells.clear();
+        let mut start = 0;
+        while start < self.keys.len() {
+            let key = self.keys[start].0;
+            let mut end = start + 1;
+            while end < self.keys.len() && self.keys[end].0 == key {
+                end += 1;
+            }
+            self.cells.insert(key, (start, end));
+            start = end;
+        }
+
+        for (i, ball) in balls.iter().enumerate() {
+            let (x, y) = cell_of(ball.pos, cell_size);
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let Some(&(start, end)) = self.cells.get(&cell_key(x + dx, y + dy)) else {
+                        continue;
+                    };
+                    for &(_, j) in &self.keys[start..end] {
+                        if j > i && overlaps(ball, &balls[j], margin) {
+                            self.pairs.push((i, j));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Sort by the left edge, then only compare against balls whose x range
+    // is still open.
+    fn sweep_and_prune(&mut self, balls: &[Ball], margin: f32) {
+        self.order.clear();
+        self.order.extend(0..balls.len());
+        self.order
+            .sort_unstable_by(|&a, &b| min_x(&balls[a]).total_cmp(&min_x(&balls[b])));
+
+        for a in 0..self.order.len() {
+            let i = self.order[a];
+            let max_x = balls[i].pos.x + balls[i].radius + margin * 2.0;
+            for &j in &self.order[(a + 1)..] {
+                if min_x(&balls[j]) > max_x {
+                    break;
+                }
+                if overlaps(&balls[i], &balls[j], margin) {
+                    self.pairs.push((i.min(j), i.max(j)));
+                }
+            }
+        }
+    }
+
+    fn brute_force(&mut self, balls: &[Ball], margin: f32) {
+        for i in 0..balls.len() {
+            for j in (i + 1)..balls.len() {
+                if overlaps(&balls[i], &balls[j], margin) {
+                    self.pairs.push((i, j));
+                }
+            }
+        }
+    }
+}
+
+impl Default for Broadphase {
+    fn default() -> Self {
+        Broadphase::new(BroadphaseKind::SpatialHash)
+    }
+}
+
+// both balls of a pair, i != j
+pub fn pair_mut<T>(items: &mut [T], i: usize, j: usize) -> (&mut T, &mut T) {
+    if i < j {
+        let (left, right) = items.split_at_mut(j);
+        (&mut left[i], &mut right[0])
+    } else {
+        let (left, right) = items.split_at_mut(i);
+        (&mut right[0], &mut left[j])
+    }
+}
+
+fn overlaps(a: &Ball, b: &Ball, margin: f32) -> bool {
+    let reach = a.radius + b.radius + margin * 2.0;
+    (a.pos.x - b.pos.x).abs() <= reach && (a.pos.y - b.pos.y).abs() <= reach
+}
+
+fn min_x(ball: &Ball) -> f32 {
+    ball.pos.x - ball.radius
+}
+
+fn cell_of(pos: raylib::Vector2, cell_size: f32) -> (i32, i32) {
+    ((pos.x / cell_size).floor() as i32, (pos.y / cell_size).floor() as i32)
+}
+
+fn cell_key(x: i32, y: i32) -> u64 {
+    ((x as u32 as u64) << 32) | y as u32 as u64
+}
@@ -0,0 +1,187 @@
+// Continuous collision detection. A body that moves further in one step
+// than the contact code can catch gets swept along its path instead, and
+// stopped at the time of impact just far enough inside whatever it hit for
+// the contact code to push it back out with a bounce.
+//
+// Every sweep returns the fraction of the displacement at which the shapes
+// first touch, None if they don't. Circles that already overlap at the start
+// are a normal contact, unless they move further in by more than the
+// contact code can catch: then they stop at 0, so nothing pressed against a
+// wall gets pushed through it.
+
+use crate::collider::edges;
+use crate::contact::closest_on_segment;
+
+// bodies moving further than this part of their size in one step get swept
+pub const SWEEP_THRESHOLD: f32 = 0.5;
+
+// how far a swept body ends up inside what it hit, in m
+pub const SLOP: f32 = 0.01;
+
+// a moving circle against a resting one
+pub fn sweep_circle_circle(
+    center: raylib::Vector2,
+    radius: f32,
+    displacement: raylib::Vector2,
+    other: raylib::Vector2,
+    other_radius: f32,
+) -> Option<f32> {
+    sweep_circle_point(center, radius + other_radius, displacement, other)
+}
+
+fn sweep_circle_point(
+    center: raylib::Vector2,
+    radius: f32,
+    displacement: raylib::Vector2,
+    point: raylib::Vector2,
+) -> Option<f32> {
+    let offset = center - point;
+    let c = offset.dot(offset) - radius * radius;
+    if c < 0.0 {
+        let inwards = -offset.normalize().dot(displacement);
+        return (inwards > radius * SWEEP_THRESHOLD).then_some(0.0);
+    }
+
+    let a = displacement.dot(displacement);
+    let b = 2.0 * offset.dot(displacement);
+    let discriminant = b * b - 4.0 * a * c;
+    if a == 0.0 || discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / (2.0 * a);
+    (0.0..=1.0).contains(&t).then_some(t)
+}
+
+// (in m, negative inside, which way is out) from the polygon to the
+// point, points are clockwise and two make a segment
+fn distance_to_polygon(point: raylib::Vector2, points: &[raylib::Vector2]) -> (f32, raylib::Vector2) {
+    let (separation, face) = edges(points)
+        .map(|(a, _, normal)| ((point - a).dot(normal), normal))
+        .max_by(|(p, _), (q, _)| p.total_cmp(q))
+        .unwrap_or((f32::INFINITY, raylib::Vector2::zero()));
+    if separation <= 0.0 && points.len() > 2 {
+        return (separation, face);
+    }
+    let closest = edges(points)
+        .map(|(a, b, _)| closest_on_segment(point, a, b))
+        .min_by(|p, q| p.distance_squared(point).total_cmp(&q.distance_squared(point)))
+        .unwrap_or(point);
+    let distance = closest.distance(point);
+    let out = if distance > 0.0 { (point - closest) / distance } else { face };
+    (distance, out)
+}
+
+// A moving circle against a resting convex polygon, which is the center
+// against the polygon grown by the radius: its edges pushed outwards plus a
+// circle around every corner.
+pub fn sweep_circle_polygon(
+    center: raylib::Vector2,
+    radius: f32,
+    displacement: raylib::Vector2,
+    points: &[raylib::Vector2],
+) -> Option<f32> {
+    let (distance, out) = distance_to_polygon(center, points);
+    if distance < radius {
+        return (-displacement.dot(out) > radius * SWEEP_THRESHOLD).then_some(0.0);
+    }
+
+    let mut first: Option<f32> = None;
+    let mut hit = |t: f32| first = Some(first.map_or(t, |first| first.min(t)));
+
+    for (a, b, normal) in edges(points) {
+        let towards = displacement.dot(normal);
+        if towards >= 0.0 {
+            continue;
+        }
+        let start = a + normal * radius;
+        let t = (start - center).dot(normal) / towards;
+        let along = (center + displacement * t - start).dot(b - a);
+        if (0.0..=1.0).contains(&t) && (0.0..=(b - a).length_squared()).contains(&along) {
+            hit(t);
+        }
+    }
+    for &point in points {
+        if let Some(t) = sweep_circle_point(center, radius, displacement, point) {
+            hit(t);
+        }
+    }
+
+    first
+}
+
+// A moving convex polygon against a resting one. They touch once the
+// displacement reaches the Minkowski difference of the two, which is
+// convex again, so it's a ray cast against a convex hull.
+pub fn sweep_polygon_polygon(
+    points: &[raylib::Vector2],
+    displacement: raylib::Vector2,
+    other: &[raylib::Vector2],
+) -> Option<f32> {
+    let differences = other.iter().flat_map(|&b| points.iter().map(move |&a| b - a)).collect();
+    let hull = convex_hull(differences);
+    if hull.len() < 3 {
+        return None;
+    }
+
+    // where the ray from the origin enters and leaves the hull
+    let mut enter = f32::NEG_INFINITY;
+    let mut leave = f32::INFINITY;
+    for (a, _, normal) in edges(&hull) {
+        let towards = displacement.dot(normal);
+        let distance = a.dot(normal);
+        if towards == 0.0 {
+            if distance < 0.0 {
+                return None;
+            }
+        } else if towards < 0.0 {
+            enter = enter.max(distance / towards);
+        } else {
+            leave = leave.min(distance / towards);
+        }
+    }
+
+    if enter < 0.0 || enter > leave || enter > 1.0 {
+        return None;
+    }
+    Some(enter)
+}
+
+// clockwise on screen, no points on the edges
+fn convex_hull(mut points: Vec<raylib::Vector2>) -> Vec<raylib::Vector2> {
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    if points.len() < 3 {
+        return points;
+    }
+
+    // monotone chain, the lower and then the upper half
+    let mut hull: Vec<raylib::Vector2> = Vec::with_capacity(points.len() + 1);
+    for pass in 0..2 {
+        let start = hull.len();
+        for &point in points.iter() {
+            while hull.len() >= start + 2 {
+                let (a, b) = (hull[hull.len() - 2], hull[hull.len() - 1]);
+                if (b - a).cross(point - b) > 0.0 {
+                    break;
+                }
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        hull.pop();
+        if pass == 0 {
+            points.reverse();
+        }
+    }
+    hull
+}
+
+// where a body sweeping from start by displacement ends up when it hits
+// at t, SLOP further along so it overlaps
+pub fn stop_at(start: raylib::Vector2, displacement: raylib::Vector2, t: f32) -> raylib::Vector2 {
+    let length = displacement.length();
+    if length == 0.0 {
+        return start;
+    }
+    let t = (t + SLOP / length).min(1.0);
+    start + displacement * t
+}
@@ -0,0 +1,123 @@
+use crate::ball::PIXELS_PER_METER;
+use crate::contact::Edges;
+use crate::material::Material;
+
+// how far the walls around the world reach outwards, anything less than
+// half of it deep in a wall still gets pushed back inside
+pub const WALL_THICKNESS: f32 = 10.0; // in m
+
+// Static geometry, never moves and can't be pushed. Two points make a line
+// segment that is solid from both sides, more make a convex polygon,
+// clockwise on screen.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Collider {
+    pub points: Vec<raylib::Vector2>, // in m
+    pub material: Material,
+    pub color: raylib::Color,
+}
+
+impl Collider {
+    fn new(points: Vec<raylib::Vector2>) -> Self {
+        Collider {
+            points,
+            material: Material::STONE,
+            color: raylib::LIGHTGRAY,
+        }
+    }
+
+    pub fn segment(from: raylib::Vector2, to: raylib::Vector2) -> Self {
+        Collider::new(vec![from, to])
+    }
+
+    // angle in rad around the center, clockwise on screen
+    pub fn rect(center: raylib::Vector2, dim: raylib::Vector2, angle: f32) -> Self {
+        let half = dim / 2.0;
+        let corners = [
+            raylib::Vector2::new(-half.x, -half.y),
+            raylib::Vector2::new(half.x, -half.y),
+            raylib::Vector2::new(half.x, half.y),
+            raylib::Vector2::new(-half.x, half.y),
+        ];
+        Collider::new(corners.iter().map(|&corner| center + corner.rotate(angle)).collect())
+    }
+
+    // takes either winding, fails on anything that isn't convex
+    pub fn polygon(points: Vec<raylib::Vector2>) -> Result<Self, String> {
+        Ok(Collider::new(convex(points)?))
+    }
+
+    // boxes just outside of bounds (in m), floor first
+    pub fn walls(bounds: raylib::Vector2, edges: Edges) -> Vec<Collider> {
+        let outside = |lo: raylib::Vector2, hi: raylib::Vector2, material: Material| Collider {
+            material,
+            color: raylib::DARKGRAY,
+            ..Collider::rect((lo + hi) / 2.0, hi - lo, 0.0)
+        };
+        let t = WALL_THICKNESS;
+        vec![
+            outside(raylib::Vector2::new(-t, bounds.y), raylib::Vector2::new(bounds.x + t, bounds.y + t), edges.floor),
+            outside(raylib::Vector2::new(-t, -t), raylib::Vector2::new(bounds.x + t, 0.0), edges.walls),
+            outside(raylib::Vector2::new(-t, 0.0), raylib::Vector2::new(0.0, bounds.y), edges.walls),
+            outside(raylib::Vector2::new(bounds.x, 0.0), raylib::Vector2::new(bounds.x + t, bounds.y), edges.walls),
+        ]
+    }
+
+    pub fn is_segment(&self) -> bool {
+        self.points.len() == 2
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (raylib::Vector2, raylib::Vector2, raylib::Vector2)> + '_ {
+        edges(&self.points)
+    }
+
+    pub unsafe fn draw(&self) {
+        if self.is_segment() {
+            let (from, to) = (self.points[0] * PIXELS_PER_METER, self.points[1] * PIXELS_PER_METER);
+            raylib::draw_line_ex(from, to, 3.0, self.color);
+            return;
+        }
+        draw_convex(&self.points, self.color);
+    }
+}
+
+// The points in clockwise order, fails if they don't make a convex polygon.
+pub fn convex(mut points: Vec<raylib::Vector2>) -> Result<Vec<raylib::Vector2>, String> {
+    if points.len() < 3 {
+        return Err("a polygon needs at least 3 points".to_string());
+    }
+
+    let turns: Vec<f32> = (0..points.len())
+        .map(|i| {
+            let (a, b, c) = (points[i], points[(i + 1) % points.len()], points[(i + 2) % points.len()]);
+            (b - a).cross(c - b)
+        })
+        .collect();
+    if turns.iter().all(|&turn| turn == 0.0) {
+        return Err("the polygon's points are all on one line".to_string());
+    }
+    if turns.iter().all(|&turn| turn <= 0.0) {
+        points.reverse();
+    } else if !turns.iter().all(|&turn| turn >= 0.0) {
+        return Err("the polygon has to be convex".to_string());
+    }
+    Ok(points)
+}
+
+// (start, end, outward normal) of every edge of clockwise points, two
+// points make a segment with one edge per side
+pub fn edges(points: &[raylib::Vector2]) -> impl Iterator<Item = (raylib::Vector2, raylib::Vector2, raylib::Vector2)> + '_ {
+    (0..points.len()).map(|i| {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        (a, b, -(b - a).perp().normalize())
+    })
+}
+
+// clockwise points in m
+pub unsafe fn draw_convex(points: &[raylib::Vector2], color: raylib::Color) {
+    let points: Vec<raylib::Vector2> = points.iter().map(|&point| point * PIXELS_PER_METER).collect();
+    // a fan, draw_triangle wants the other winding
+    for i in 1..points.len() - 1 {
+        raylib::draw_triangle(points[0], points[i + 1], points[i], color);
+    }
+}
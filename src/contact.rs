@@ -0,0 +1,334 @@
+use crate::ball::Ball;
+use crate::collider::{edges, Collider};
+use crate::material::Material;
+use crate::polygon::Polygon;
+
+// for masses and moments of inertia, infinite means it can't be moved
+pub(crate) fn inverse(value: f32) -> f32 {
+    if value > 0.0 && value.is_finite() {
+        return 1.0 / value;
+    }
+    0.0
+}
+
+// What the walls around the world are made of, walls means the sides and
+// the ceiling.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Edges {
+    pub floor: Material,
+    pub walls: Material,
+}
+
+impl Default for Edges {
+    fn default() -> Self {
+        Edges {
+            floor: Material::STONE,
+            walls: Material::STONE,
+        }
+    }
+}
+
+// Up to two points where two shapes overlap, normal points from the first
+// shape to the second.
+#[derive(Clone, Copy, Debug)]
+pub struct Manifold {
+    pub normal: raylib::Vector2,
+    pub points: [(raylib::Vector2, f32); 2], // (point, penetration)
+    pub len: usize,
+}
+
+impl Manifold {
+    fn single(normal: raylib::Vector2, point: raylib::Vector2, penetration: f32) -> Self {
+        Manifold {
+            normal,
+            points: [(point, penetration), (point, 0.0)],
+            len: 1,
+        }
+    }
+
+    pub fn points(&self) -> &[(raylib::Vector2, f32)] {
+        &self.points[..self.len]
+    }
+
+    // the same contact seen from the other shape
+    pub fn flipped(mut self) -> Self {
+        self.normal = -self.normal;
+        self
+    }
+}
+
+pub fn circle_circle(a: &Ball, b: &Ball) -> Option<Manifold> {
+    if !raylib::check_collision_circles(a.pos, a.radius, b.pos, b.radius) {
+        return None;
+    }
+
+    let delta = b.pos - a.pos;
+    let dist = delta.pythagorean();
+    let min_dist = a.radius + b.radius;
+    if !(dist < min_dist && dist > 0.0) {
+        return None;
+    }
+
+    let normal = delta / dist;
+    Some(Manifold::single(normal, a.pos + normal * a.radius, min_dist - dist))
+}
+
+// circle against an axis aligned box, including the circle center being
+// inside the box, normal points from the box to the ball
+pub fn circle_rect(ball: &Ball, rect: raylib::Rectangle) -> Option<Manifold> {
+    if !raylib::check_collision_circle_rec(ball.pos, ball.radius, rect) {
+        return None;
+    }
+
+    let closest = raylib::Vector2 {
+        x: ball.pos.x.clamp(rect.x, rect.x + rect.width),
+        y: ball.pos.y.clamp(rect.y, rect.y + rect.height),
+    };
+
+    let delta = ball.pos - closest;
+    let dist = delta.pythagorean();
+
+    let (normal, penetration) = if dist > 0.0 {
+        if dist >= ball.radius {
+            return None;
+        }
+        (delta / dist, ball.radius - dist)
+    } else {
+        // center is inside, leave through the closest side
+        let left = ball.pos.x - rect.x;
+        let right = rect.x + rect.width - ball.pos.x;
+        let top = ball.pos.y - rect.y;
+        let bottom = rect.y + rect.height - ball.pos.y;
+        let min = left.min(right).min(top).min(bottom);
+
+        let normal = if min == left {
+            raylib::Vector2::new(-1.0, 0.0)
+        } else if min == right {
+            raylib::Vector2::new(1.0, 0.0)
+        } else if min == top {
+            raylib::Vector2::new(0.0, -1.0)
+        } else {
+            raylib::Vector2::new(0.0, 1.0)
+        };
+        (normal, min + ball.radius)
+    };
+
+    // the deepest point of the ball, on the surface of the box
+    let point = ball.pos - normal * (ball.radius - penetration);
+    Some(Manifold::single(normal, point, penetration))
+}
+
+// two axis aligned boxes, separated along the axis they overlap least on
+pub fn rect_rect(rect_a: raylib::Rectangle, rect_b: raylib::Rectangle) -> Option<Manifold> {
+    if !raylib::check_collision_recs(rect_a, rect_b) {
+        return None;
+    }
+
+    let overlap_x = (rect_a.x + rect_a.width).min(rect_b.x + rect_b.width) - rect_a.x.max(rect_b.x);
+    let overlap_y =
+        (rect_a.y + rect_a.height).min(rect_b.y + rect_b.height) - rect_a.y.max(rect_b.y);
+
+    let center_a = raylib::Vector2::new(rect_a.x + rect_a.width / 2.0, rect_a.y + rect_a.height / 2.0);
+    let center_b = raylib::Vector2::new(rect_b.x + rect_b.width / 2.0, rect_b.y + rect_b.height / 2.0);
+
+    let (normal, penetration) = if overlap_x < overlap_y {
+        let sign = if center_b.x < center_a.x { -1.0 } else { 1.0 };
+        (raylib::Vector2::new(sign, 0.0), overlap_x)
+    } else {
+        let sign = if center_b.y < center_a.y { -1.0 } else { 1.0 };
+        (raylib::Vector2::new(0.0, sign), overlap_y)
+    };
+
+    // middle of the overlapping area
+    let overlap_min = raylib::Vector2::new(rect_a.x.max(rect_b.x), rect_a.y.max(rect_b.y));
+    let point = overlap_min + raylib::Vector2::new(overlap_x, overlap_y) / 2.0;
+    Some(Manifold::single(normal, point, penetration))
+}
+
+// (normal pointing from the polygon to the ball, penetration) if they
+// touch, points are clockwise and two of them make a segment
+fn circle_polygon_contact(ball: &Ball, points: &[raylib::Vector2]) -> Option<(raylib::Vector2, f32)> {
+    // the edge the center is furthest outside of, inside the polygon if
+    // that is negative
+    let mut separation = f32::NEG_INFINITY;
+    let mut face = raylib::Vector2::zero();
+    for (a, _, normal) in edges(points) {
+        let distance = (ball.pos - a).dot(normal);
+        if distance > separation {
+            separation = distance;
+            face = normal;
+        }
+    }
+    if separation >= ball.radius {
+        return None;
+    }
+    // a segment has no inside, only the line through it
+    if separation <= 0.0 && points.len() > 2 {
+        return Some((face, ball.radius - separation));
+    }
+
+    let closest = edges(points)
+        .map(|(a, b, _)| closest_on_segment(ball.pos, a, b))
+        .min_by(|p, q| p.distance_squared(ball.pos).total_cmp(&q.distance_squared(ball.pos)))?;
+    let delta = ball.pos - closest;
+    let dist = delta.pythagorean();
+    if dist >= ball.radius {
+        return None;
+    }
+    if dist == 0.0 {
+        return Some((face, ball.radius));
+    }
+    Some((delta / dist, ball.radius - dist))
+}
+
+pub(crate) fn closest_on_segment(point: raylib::Vector2, a: raylib::Vector2, b: raylib::Vector2) -> raylib::Vector2 {
+    let edge = b - a;
+    let length_squared = edge.length_squared();
+    if length_squared == 0.0 {
+        return a;
+    }
+    let t = ((point - a).dot(edge) / length_squared).clamp(0.0, 1.0);
+    a + edge * t
+}
+
+// points are clockwise, two of them make a segment, normal points from
+// them to the ball
+pub fn circle_convex(ball: &Ball, points: &[raylib::Vector2]) -> Option<Manifold> {
+    let (normal, penetration) = circle_polygon_contact(ball, points)?;
+    let point = ball.pos - normal * (ball.radius - penetration);
+    Some(Manifold::single(normal, point, penetration))
+}
+
+// (min, max) of points projected onto axis
+fn project(points: &[raylib::Vector2], axis: raylib::Vector2) -> (f32, f32) {
+    points.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), point| {
+        let along = point.dot(axis);
+        (min.min(along), max.max(along))
+    })
+}
+
+// Separating axes between the box and the collider, the box leaves along
+// the one it overlaps least on. Like against a wall, the contact acts on
+// the box's center and never makes it turn. normal points from the
+// collider to the box.
+pub fn rect_collider(rect: raylib::Rectangle, collider: &Collider) -> Option<Manifold> {
+    let corners = rect_points(rect);
+
+    let axes = [raylib::Vector2::new(1.0, 0.0), raylib::Vector2::new(0.0, 1.0)];
+    let edge_axes = collider.edges().map(|(_, _, normal)| normal);
+
+    let mut normal = raylib::Vector2::zero();
+    let mut penetration = f32::INFINITY;
+    for axis in axes.into_iter().chain(edge_axes) {
+        let (box_min, box_max) = project(&corners, axis);
+        let (collider_min, collider_max) = project(&collider.points, axis);
+        let forward = collider_max - box_min;
+        let backward = box_max - collider_min;
+        if forward <= 0.0 || backward <= 0.0 {
+            return None;
+        }
+        if forward < penetration {
+            (normal, penetration) = (axis, forward);
+        }
+        if backward < penetration {
+            (normal, penetration) = (-axis, backward);
+        }
+    }
+
+    let center = raylib::Vector2::new(rect.x + rect.width / 2.0, rect.y + rect.height / 2.0);
+    Some(Manifold::single(normal, center, penetration))
+}
+
+// (separation, edge index) of the edge of a that b is furthest outside of,
+// negative means b reaches into a past every edge
+fn max_separation(a: &[raylib::Vector2], b: &[raylib::Vector2]) -> (f32, usize) {
+    let mut best = (f32::NEG_INFINITY, 0);
+    for (i, (start, _, normal)) in edges(a).enumerate() {
+        let separation = b.iter().map(|&point| (point - start).dot(normal)).fold(f32::INFINITY, f32::min);
+        if separation > best.0 {
+            best = (separation, i);
+        }
+    }
+    best
+}
+
+// what is left of the segment from..to on the side of the line through
+// point where (p - point).dot(dir) >= 0
+fn clip(segment: [raylib::Vector2; 2], point: raylib::Vector2, dir: raylib::Vector2) -> Option<[raylib::Vector2; 2]> {
+    let [from, to] = segment;
+    let (d_from, d_to) = ((from - point).dot(dir), (to - point).dot(dir));
+    match (d_from >= 0.0, d_to >= 0.0) {
+        (true, true) => Some(segment),
+        (false, false) => None,
+        (true, false) => Some([from, from.lerp(to, d_from / (d_from - d_to))]),
+        (false, true) => Some([from.lerp(to, d_from / (d_from - d_to)), to]),
+    }
+}
+
+// Separating axis test between two convex polygons (clockwise, two points
+// make a segment). The edge with the least overlap is the reference face,
+// the most opposing edge of the other shape gets clipped to its sides and
+// whatever of it is below the reference face is in contact.
+pub fn polygon_manifold(a: &[raylib::Vector2], b: &[raylib::Vector2]) -> Option<Manifold> {
+    let (separation_a, edge_a) = max_separation(a, b);
+    if separation_a > 0.0 {
+        return None;
+    }
+    let (separation_b, edge_b) = max_separation(b, a);
+    if separation_b > 0.0 {
+        return None;
+    }
+
+    // prefer a's face unless b's is clearly better, keeps it from flipping
+    // between the two on faces that are flush
+    let flip = separation_b > separation_a + 0.001;
+    let (reference, incident, edge) = if flip { (b, a, edge_b) } else { (a, b, edge_a) };
+
+    let (start, end, normal) = edges(reference).nth(edge)?;
+    let (incident_edge, _) = edges(incident)
+        .enumerate()
+        .map(|(i, (_, _, incident_normal))| (i, incident_normal.dot(normal)))
+        .min_by(|(_, p), (_, q)| p.total_cmp(q))?;
+    let (from, to, _) = edges(incident).nth(incident_edge)?;
+
+    let tangent = (end - start).normalize();
+    let clipped = clip([from, to], start, tangent).and_then(|segment| clip(segment, end, -tangent))?;
+
+    let mut manifold = Manifold {
+        normal: if flip { -normal } else { normal },
+        points: [(raylib::Vector2::zero(), 0.0); 2],
+        len: 0,
+    };
+    for point in clipped {
+        let separation = (point - start).dot(normal);
+        if separation <= 0.0 {
+            manifold.points[manifold.len] = (point, -separation);
+            manifold.len += 1;
+        }
+    }
+    (manifold.len > 0).then_some(manifold)
+}
+
+pub(crate) fn rect_points(rect: raylib::Rectangle) -> [raylib::Vector2; 4] {
+    [
+        raylib::Vector2::new(rect.x, rect.y),
+        raylib::Vector2::new(rect.x + rect.width, rect.y),
+        raylib::Vector2::new(rect.x + rect.width, rect.y + rect.height),
+        raylib::Vector2::new(rect.x, rect.y + rect.height),
+    ]
+}
+
+pub fn polygon_polygon(a: &Polygon, b: &Polygon) -> Option<Manifold> {
+    if a.pos.distance(b.pos) > a.radius() + b.radius() {
+        return None;
+    }
+    polygon_manifold(&a.vertices(), &b.vertices())
+}
+
+// normal points from the polygon to the ball
+pub fn circle_polygon(ball: &Ball, polygon: &Polygon) -> Option<Manifold> {
+    if ball.pos.distance(polygon.pos) > ball.radius + polygon.radius() {
+        return None;
+    }
+    circle_convex(ball, &polygon.vertices())
+}
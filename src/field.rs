@@ -0,0 +1,225 @@
+// Force fields push bodies around on top of gravity, wherever they are and
+// whatever they touch. Every body sums the forces on it each step and the
+// integrator turns them into motion, see Forces::on.
+//
+// Point and vortex fields are strongest at their center. With an inverse
+// square falloff they reach across the whole world, softened close to the
+// center so nothing gets flung out of it, with a linear one they fade out
+// at radius.
+
+use std::f32::consts::PI;
+
+use crate::ball::PIXELS_PER_METER;
+use crate::fluid::{Fluid, Pool};
+use crate::nbody::Quadtree;
+
+// in m, inverse square fields act as if nothing got closer than this
+pub const SOFTENING: f32 = 0.5;
+
+// what a middle click makes, see Field::at
+pub const FIELD_STRENGTH: f32 = 30.0; // in m/s² at the center
+pub const FIELD_RADIUS: f32 = 5.0; // in m
+pub const FIELD_REGION: f32 = 4.0; // in m, the side of a uniform field's square
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FieldKind {
+    Uniform, // the same acceleration everywhere, wind or a second gravity
+    Point,   // pulls towards the center, or pushes away with a negative strength
+    Vortex,  // swirls clockwise on screen around the center, or the other way
+}
+
+impl FieldKind {
+    pub const ALL: [FieldKind; 3] = [FieldKind::Uniform, FieldKind::Point, FieldKind::Vortex];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FieldKind::Uniform => "uniform",
+            FieldKind::Point => "point",
+            FieldKind::Vortex => "vortex",
+        }
+    }
+
+    pub fn next(self) -> FieldKind {
+        match self {
+            FieldKind::Uniform => FieldKind::Point,
+            FieldKind::Point => FieldKind::Vortex,
+            FieldKind::Vortex => FieldKind::Uniform,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Falloff {
+    InverseSquare, // strength over the distance squared
+    Linear,        // strength at the center down to nothing at radius
+}
+
+impl Falloff {
+    pub const ALL: [Falloff; 2] = [Falloff::InverseSquare, Falloff::Linear];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Falloff::InverseSquare => "inverse-square",
+            Falloff::Linear => "linear",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Field {
+    pub kind: FieldKind,
+    pub acceleration: raylib::Vector2, // in m/s², uniform fields only
+    pub center: raylib::Vector2,       // in m, point and vortex fields
+    // in m³/s² with an inverse square falloff, in m/s² at the center with a
+    // linear one. negative pushes away, or swirls counterclockwise
+    pub strength: f32,
+    pub falloff: Falloff,
+    pub radius: f32,                       // in m, where a linear falloff reaches 0
+    pub region: Option<raylib::Rectangle>, // in m, the field only acts inside it
+}
+
+impl Field {
+    pub fn uniform(acceleration: raylib::Vector2) -> Self {
+        Field {
+            kind: FieldKind::Uniform,
+            acceleration,
+            center: raylib::Vector2::zero(),
+            strength: 0.0,
+            falloff: Falloff::Linear,
+            radius: FIELD_RADIUS,
+            region: None,
+        }
+    }
+
+    // a point or vortex field, linear falloff
+    pub fn around(kind: FieldKind, center: raylib::Vector2, strength: f32) -> Self {
+        Field { kind, center, strength, ..Field::uniform(raylib::Vector2::zero()) }
+    }
+
+    // what a middle click at point makes, negative turns it around: a
+    // repulsor, a counterclockwise vortex, or a square that pushes down
+    // instead of lifting
+    pub fn at(kind: FieldKind, point: raylib::Vector2, negative: bool) -> Self {
+        let strength = if negative { -FIELD_STRENGTH } else { FIELD_STRENGTH };
+        match kind {
+            FieldKind::Uniform => {
+                let half = FIELD_REGION / 2.0;
+                let region = raylib::Rectangle { x: point.x - half, y: point.y - half, width: FIELD_REGION, height: FIELD_REGION };
+                Field { region: Some(region), ..Field::uniform(raylib::Vector2::new(0.0, -strength)) }
+            }
+            FieldKind::Point | FieldKind::Vortex => Field::around(kind, point, strength),
+        }
+    }
+
+    // in m/s², what the field does to anything at pos
+    pub fn acceleration_at(&self, pos: raylib::Vector2) -> raylib::Vector2 {
+        if let Some(region) = self.region {
+            if !raylib::check_collision_point_rec(pos, region) {
+                return raylib::Vector2::zero();
+            }
+        }
+        if self.kind == FieldKind::Uniform {
+            return self.acceleration;
+        }
+
+        let offset = pos - self.center;
+        let distance = offset.length();
+        if distance == 0.0 {
+            return raylib::Vector2::zero();
+        }
+        let magnitude = match self.falloff {
+            Falloff::InverseSquare => self.strength / (distance * distance + SOFTENING * SOFTENING),
+            Falloff::Linear => self.strength * (1.0 - distance / self.radius).max(0.0),
+        };
+        let direction = match self.kind {
+            FieldKind::Point => -offset / distance,
+            _ => offset.perp() / distance,
+        };
+        direction * magnitude
+    }
+
+    // in N, on a body of mass kg at pos
+    pub fn force(&self, pos: raylib::Vector2, mass: f32) -> raylib::Vector2 {
+        self.acceleration_at(pos) * mass
+    }
+
+    pub unsafe fn draw(&self) {
+        let color = if self.strength < 0.0 || self.acceleration.y > 0.0 { raylib::ORANGE } else { raylib::SKYBLUE };
+        let center = match self.region {
+            Some(region) => {
+                let rect = raylib::Rectangle {
+                    x: region.x * PIXELS_PER_METER,
+                    y: region.y * PIXELS_PER_METER,
+                    width: region.width * PIXELS_PER_METER,
+                    height: region.height * PIXELS_PER_METER,
+                };
+                raylib::draw_rectangle_lines_ex(rect, 1.0, color);
+                raylib::Vector2::new(region.x + region.width / 2.0, region.y + region.height / 2.0)
+            }
+            None => self.center,
+        };
+        if self.kind == FieldKind::Uniform {
+            // which way it pushes, a meter for every 10 m/s²
+            let tip = center + self.acceleration / 10.0;
+            raylib::draw_line_ex(center * PIXELS_PER_METER, tip * PIXELS_PER_METER, 2.0, color);
+            raylib::draw_circle_v(tip * PIXELS_PER_METER, 3.0, color);
+            return;
+        }
+
+        let radius = match self.falloff {
+            Falloff::Linear => self.radius,
+            Falloff::InverseSquare => SOFTENING,
+        };
+        raylib::draw_circle_lines_v(self.center * PIXELS_PER_METER, radius * PIXELS_PER_METER, color);
+        if self.kind == FieldKind::Vortex {
+            // a spoke on the rim points the way it swirls
+            let rim = self.center + raylib::Vector2::new(radius, 0.0);
+            let spin = raylib::Vector2::new(0.0, radius / 2.0 * self.strength.signum());
+            raylib::draw_line_ex(rim * PIXELS_PER_METER, (rim + spin) * PIXELS_PER_METER, 2.0, color);
+        } else {
+            raylib::draw_circle_v(self.center * PIXELS_PER_METER, 3.0, color);
+        }
+    }
+}
+
+// Everything that pushes a body between contacts.
+#[derive(Clone, Copy)]
+pub struct Forces<'a> {
+    pub gravity: raylib::Vector2, // in m/s²
+    pub fields: &'a [Field],
+    // balls pulling on each other, the tree and which ball asks, see nbody.rs
+    pub attraction: Option<(&'a Quadtree, usize)>,
+    pub air: Fluid, // everywhere outside the pools, see fluid.rs
+    pub pools: &'a [Pool],
+}
+
+impl Forces<'_> {
+    // in N, summed over gravity, every field and the other balls, on a body
+    // of mass kg at pos
+    pub fn on(&self, pos: raylib::Vector2, mass: f32) -> raylib::Vector2 {
+        let total = self.fields.iter().fold(self.gravity * mass, |total, field| total + field.force(pos, mass));
+        match self.attraction {
+            Some((tree, own)) => total + tree.acceleration(pos, own) * mass,
+            None => total,
+        }
+    }
+
+    // the same plus drag and buoyancy, on a ball of radius going velocity.
+    // the fluids hold it up against gravity only
+    pub fn on_ball(&self, pos: raylib::Vector2, velocity: raylib::Vector2, radius: f32, mass: f32) -> raylib::Vector2 {
+        let area = PI * radius * radius;
+        let lift = |fluid: &Fluid, submerged: f32| -self.gravity * (fluid.density * submerged);
+        let mut total = self.on(pos, mass);
+        let mut in_air = area;
+        for pool in self.pools.iter() {
+            let submerged = pool.submerged(pos, radius);
+            if submerged > 0.0 {
+                total += lift(&pool.fluid, submerged) + pool.fluid.drag(velocity, radius) * (submerged / area);
+                in_air -= submerged;
+            }
+        }
+        // pools on top of each other count twice, there's no air left then
+        let in_air = in_air.max(0.0);
+        total + lift(&self.air, in_air) + self.air.drag(velocity, radius) * (in_air / area)
+    }
+}
@@ -0,0 +1,85 @@
+// Balls moving through air or water get slowed down by it, and hold up by
+// as much of it as they push aside, so they fall no faster than their
+// terminal velocity and float when they're lighter than water. Pools are
+// boxes of a fluid, everywhere else is the world's air.
+//
+// Drag has a linear part from the fluid's viscosity, which is all there is
+// for slow balls, and a quadratic part from its density, which wins once
+// they go fast. Both grow with the radius.
+
+use std::f32::consts::PI;
+
+use crate::ball::PIXELS_PER_METER;
+
+// of a cylinder side on, the world is flat
+pub const DRAG_COEFFICIENT: f32 = 1.2;
+
+pub const WATER_COLOR: raylib::Color = raylib::Color { r: 0, g: 121, b: 241, a: 90 };
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Fluid {
+    pub density: f32,   // in kg/m², like the materials
+    pub viscosity: f32, // in kg/(m·s)
+}
+
+impl Fluid {
+    // wood rides high in it, ice and rubber float low, everything else sinks
+    pub const WATER: Fluid = Fluid { density: 0.27, viscosity: 0.05 };
+
+    // in N, on a ball of radius going velocity through it
+    pub fn drag(&self, velocity: raylib::Vector2, radius: f32) -> raylib::Vector2 {
+        let linear = 6.0 * PI * self.viscosity * radius;
+        let quadratic = 0.5 * self.density * DRAG_COEFFICIENT * 2.0 * radius * velocity.length();
+        -velocity * (linear + quadratic)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pool {
+    pub rect: raylib::Rectangle, // in m
+    pub fluid: Fluid,
+    pub color: raylib::Color, // see through, the balls in it still show
+}
+
+impl Pool {
+    pub fn new(rect: raylib::Rectangle) -> Self {
+        Pool { rect, fluid: Fluid::WATER, color: WATER_COLOR }
+    }
+
+    // in m², how much of a ball of radius at pos is in the pool
+    pub fn submerged(&self, pos: raylib::Vector2, radius: f32) -> f32 {
+        let (left, top) = (self.rect.x - pos.x, self.rect.y - pos.y);
+        let (right, bottom) = (left + self.rect.width, top + self.rect.height);
+        let area = |x, y| circle_area_before(x, y, radius);
+        (area(right, bottom) - area(left, bottom) - area(right, top) + area(left, top)).max(0.0)
+    }
+
+    pub unsafe fn draw(&self) {
+        let rect = raylib::Rectangle {
+            x: self.rect.x * PIXELS_PER_METER,
+            y: self.rect.y * PIXELS_PER_METER,
+            width: self.rect.width * PIXELS_PER_METER,
+            height: self.rect.height * PIXELS_PER_METER,
+        };
+        raylib::draw_rectangle_rec(rect, self.color);
+    }
+}
+
+// in m², the part of a circle of radius around (0, 0) left of x and above y
+fn circle_area_before(x: f32, y: f32, radius: f32) -> f32 {
+    let (x, y) = (x.clamp(-radius, radius), y.clamp(-radius, radius));
+    let squared = radius * radius;
+    // the area under half the circle from its center to u
+    let half = |u: f32| (u * (squared - u * u).max(0.0).sqrt() + squared * (u / radius).clamp(-1.0, 1.0).asin()) / 2.0;
+    // where the line at y cuts the circle
+    let cut = (squared - y * y).max(0.0).sqrt();
+
+    // left of x only, pieces that start right of it are empty
+    let piece = |from: f32, to: f32| (from, to.min(x).max(from));
+    // between the cuts the circle is cut off at y, outside them it is
+    // either all above y or all below
+    let (from, to) = piece(-cut, cut);
+    let middle = y * (to - from) + half(to) - half(from);
+    let outside = [piece(-radius, -cut), piece(cut, radius)].into_iter().map(|(from, to)| 2.0 * (half(to) - half(from)));
+    middle + if y > 0.0 { outside.sum() } else { 0.0 }
+}
@@ -0,0 +1,99 @@
+use crate::ball::{Ball, PIXELS_PER_METER};
+use crate::contact::{polygon_manifold, Manifold};
+use crate::material::Material;
+use crate::obj::{Collision, RectBody};
+
+#[derive(Clone, Copy)]
+pub struct Hoop {
+    pub pos: raylib::Vector2, // in m
+    pub dim: raylib::Vector2, // in m
+    pub color: raylib::Color,
+    pub material: Material,
+    pub follow_edge: bool,    // keeps itself centered on the right edge
+}
+
+impl Hoop {
+    pub unsafe fn draw(&self) {
+        raylib::draw_rectangle_v(
+            self.pos.mult_value(PIXELS_PER_METER),
+            self.dim.mult_value(PIXELS_PER_METER),
+            self.color,
+        );
+    }
+
+    // bounds of the world in m, returns which balls it took in the order
+    // they went, every index is into balls as they were right then
+    pub fn update(&mut self, bounds: raylib::Vector2, balls: &mut Vec<Ball>) -> Vec<usize> {
+        if self.follow_edge {
+            self.pos.x = bounds.x - self.dim.x;
+            self.pos.y = bounds.y / 2.0 - (self.dim.y / 2.0);
+        }
+
+        let mut removed = Vec::new();
+        let mut i = 0;
+        while i < balls.len() {
+            if self.contains(&balls[i]) {
+                balls.remove(i);
+                removed.push(i);
+                continue;
+            }
+            i += 1;
+        }
+        removed
+    }
+
+    pub fn contains(&self, ball: &Ball) -> bool {
+        raylib::check_collision_circle_rec(ball.pos, ball.radius, self.rect())
+    }
+
+    pub fn new() -> Hoop {
+        Hoop {
+            pos: raylib::Vector2::zero(),
+            dim: raylib::Vector2 { x: 1.0, y: 2.0 },
+            color: raylib::RAYWHITE,
+            material: Material::WOOD,
+            follow_edge: true,
+        }
+    }
+}
+
+impl Default for Hoop {
+    fn default() -> Self {
+        Hoop::new()
+    }
+}
+
+// Solid for the player and other boxes, balls fall into it instead.
+impl RectBody for Hoop {
+    fn rect(&self) -> raylib::Rectangle {
+        raylib::Rectangle {
+            x: self.pos.x,
+            y: self.pos.y,
+            width: self.dim.x,
+            height: self.dim.y,
+        }
+    }
+
+    fn velocity(&self) -> raylib::Vector2 {
+        raylib::Vector2::zero()
+    }
+
+    fn inv_mass(&self) -> f32 {
+        0.0
+    }
+
+    fn material(&self) -> Material {
+        self.material
+    }
+}
+
+impl Collision for Hoop {
+    // balls never touch it, the hoop swallows them in update()
+    fn contact_with_ball(&self, _ball: &Ball) -> Option<Manifold> {
+        None
+    }
+
+    fn contact_with_rect<T: RectBody>(&self, rect: &T) -> Option<Manifold> {
+        polygon_manifold(&self.corners(), &rect.corners())
+    }
+}
@@ -0,0 +1,98 @@
+// How a body moves under the forces on it between two steps, before the
+// solver sees it. acceleration is in m/s² and may depend on where the body
+// is and how fast it goes, the methods only differ once it does, or in how
+// they treat the start and end of the step.
+//
+// Bodies the solver pushed on move by the velocity it left, which is
+// semi-implicit Euler whatever the method, see Solver::finish. Contacts
+// and joints are solved on velocities and would fight anything else.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Integrator {
+    ExplicitEuler, // moves by the old velocity, gains energy
+    #[default]
+    SemiImplicitEuler, // moves by the new velocity, what the solver assumes
+    VelocityVerlet,    // kick, drift, kick
+    PositionVerlet,    // drift, kick, drift
+    Rk4,               // four samples of the acceleration, weighted
+}
+
+impl Integrator {
+    pub const ALL: [Integrator; 5] = [
+        Integrator::ExplicitEuler,
+        Integrator::SemiImplicitEuler,
+        Integrator::VelocityVerlet,
+        Integrator::PositionVerlet,
+        Integrator::Rk4,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Integrator::ExplicitEuler => "explicit-euler",
+            Integrator::SemiImplicitEuler => "semi-implicit-euler",
+            Integrator::VelocityVerlet => "velocity-verlet",
+            Integrator::PositionVerlet => "position-verlet",
+            Integrator::Rk4 => "rk4",
+        }
+    }
+
+    pub fn next(self) -> Integrator {
+        let i = Integrator::ALL.iter().position(|&integrator| integrator == self).unwrap_or(0);
+        Integrator::ALL[(i + 1) % Integrator::ALL.len()]
+    }
+
+    // how far a body at pos going velocity moves in dt, and how fast it
+    // goes at the end of it
+    pub fn advance(
+        self,
+        pos: raylib::Vector2,
+        velocity: raylib::Vector2,
+        dt: f32,
+        acceleration: impl Fn(raylib::Vector2, raylib::Vector2) -> raylib::Vector2,
+    ) -> (raylib::Vector2, raylib::Vector2) {
+        match self {
+            Integrator::ExplicitEuler => (velocity * dt, velocity + acceleration(pos, velocity) * dt),
+            Integrator::SemiImplicitEuler => {
+                let velocity = velocity + acceleration(pos, velocity) * dt;
+                (velocity * dt, velocity)
+            }
+            Integrator::VelocityVerlet => {
+                let start = acceleration(pos, velocity);
+                let displacement = velocity * dt + start * (dt * dt / 2.0);
+                // the velocity at the end is guessed with the old acceleration
+                let end = acceleration(pos + displacement, velocity + start * dt);
+                (displacement, velocity + (start + end) * (dt / 2.0))
+            }
+            Integrator::PositionVerlet => {
+                let half = velocity * (dt / 2.0);
+                let end = velocity + acceleration(pos + half, velocity) * dt;
+                (half + end * (dt / 2.0), end)
+            }
+            Integrator::Rk4 => {
+                let v1 = velocity;
+                let a1 = acceleration(pos, v1);
+                let v2 = velocity + a1 * (dt / 2.0);
+                let a2 = acceleration(pos + v1 * (dt / 2.0), v2);
+                let v3 = velocity + a2 * (dt / 2.0);
+                let a3 = acceleration(pos + v2 * (dt / 2.0), v3);
+                let v4 = velocity + a3 * dt;
+                let a4 = acceleration(pos + v3 * dt, v4);
+                ((v1 + (v2 + v3) * 2.0 + v4) * (dt / 6.0), velocity + (a1 + (a2 + a3) * 2.0 + a4) * (dt / 6.0))
+            }
+        }
+    }
+
+    // the velocity a body ends the step with, and how far it moves on top
+    // of that velocity times dt, 0 for semi-implicit Euler. the solver works
+    // on the velocity, the drift is added when the body moves
+    pub fn step(
+        self,
+        pos: raylib::Vector2,
+        velocity: raylib::Vector2,
+        dt: f32,
+        acceleration: impl Fn(raylib::Vector2, raylib::Vector2) -> raylib::Vector2,
+    ) -> (raylib::Vector2, raylib::Vector2) {
+        let (displacement, velocity) = self.advance(pos, velocity, dt, acceleration);
+        (velocity, displacement - velocity * dt)
+    }
+}
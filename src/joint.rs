@@ -0,0 +1,247 @@
+// Joints hold two bodies together, or a body to a point in the world.
+// They're solved together with the contacts, see Solver::add_joint.
+//
+// Anchors are relative to the body's center and unrotated, so they turn
+// with it. On BodyId::Fixed the anchor is the point in the world.
+
+use crate::ball::{Ball, PIXELS_PER_METER};
+use crate::block::Block;
+use crate::material::Material;
+use crate::obj::RectBody;
+use crate::player::Player;
+use crate::polygon::Polygon;
+use crate::solver::BodyId;
+
+// what springs get when nothing else is given
+pub const SPRING_STIFFNESS: f32 = 50.0; // in N/m
+pub const SPRING_DAMPING: f32 = 1.0; // in N·s/m
+
+pub const ROPE_RADIUS: f32 = 0.1; // in m
+// links of a rope are this many radii apart, so they only touch when it's slack
+pub const ROPE_SPACING: f32 = 3.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum JointKind {
+    Distance, // keeps the anchors exactly length apart
+    Spring,   // pulls and pushes towards length, Hooke's law with damping
+    Rope,     // keeps the anchors at most length apart, it only pulls
+    Pin,      // keeps the anchors together, the bodies turn freely around it
+}
+
+impl JointKind {
+    pub const ALL: [JointKind; 4] = [JointKind::Distance, JointKind::Spring, JointKind::Rope, JointKind::Pin];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            JointKind::Distance => "distance",
+            JointKind::Spring => "spring",
+            JointKind::Rope => "rope",
+            JointKind::Pin => "pin",
+        }
+    }
+
+    pub fn next(self) -> JointKind {
+        match self {
+            JointKind::Distance => JointKind::Spring,
+            JointKind::Spring => JointKind::Rope,
+            JointKind::Rope => JointKind::Pin,
+            JointKind::Pin => JointKind::Distance,
+        }
+    }
+
+    pub fn color(self) -> raylib::Color {
+        match self {
+            JointKind::Distance => raylib::RAYWHITE,
+            JointKind::Spring => raylib::YELLOW,
+            JointKind::Rope => raylib::BEIGE,
+            JointKind::Pin => raylib::RED,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Joint {
+    pub kind: JointKind,
+    pub a: BodyId,
+    pub b: BodyId,
+    pub anchor_a: raylib::Vector2, // in m
+    pub anchor_b: raylib::Vector2, // in m
+    pub length: f32,               // in m, the rest length of springs, unused by pins
+    pub stiffness: f32,            // in N/m, springs only
+    pub damping: f32,              // in N·s/m, springs only
+    // in N·s, what the solver ended the last step with, for warm starting.
+    // along the joint in x for everything but pins
+    pub impulse: raylib::Vector2,
+}
+
+impl Joint {
+    pub fn new(kind: JointKind, a: BodyId, anchor_a: raylib::Vector2, b: BodyId, anchor_b: raylib::Vector2) -> Self {
+        Joint {
+            kind,
+            a,
+            b,
+            anchor_a,
+            anchor_b,
+            length: 0.0,
+            stiffness: SPRING_STIFFNESS,
+            damping: SPRING_DAMPING,
+            impulse: raylib::Vector2::zero(),
+        }
+    }
+
+    // held by the world at both ends, as long as they are apart now, see
+    // Bodies::attach
+    pub fn between(kind: JointKind, from: raylib::Vector2, to: raylib::Vector2) -> Self {
+        let to = if kind == JointKind::Pin { from } else { to };
+        let mut joint = Joint::new(kind, BodyId::Fixed, from, BodyId::Fixed, to);
+        joint.length = from.distance(to);
+        joint
+    }
+
+    // a and b are where the anchors are in the world, in m
+    pub unsafe fn draw(&self, a: raylib::Vector2, b: raylib::Vector2) {
+        let color = self.kind.color();
+        if self.kind == JointKind::Pin {
+            raylib::draw_circle_lines_v(a * PIXELS_PER_METER, 4.0, color);
+            return;
+        }
+        raylib::draw_line_ex(a * PIXELS_PER_METER, b * PIXELS_PER_METER, 2.0, color);
+    }
+}
+
+// The bodies joints can hold on to, borrowed from a World or a Scene.
+pub struct Bodies<'a> {
+    pub balls: &'a [Ball],
+    pub blocks: &'a [Block],
+    pub polygons: &'a [Polygon],
+    pub player: &'a Player,
+}
+
+impl Bodies<'_> {
+    // the body under point, Fixed if nothing that moves is there
+    pub fn at(&self, point: raylib::Vector2) -> BodyId {
+        let inside = |rect: raylib::Rectangle| raylib::check_collision_point_rec(point, rect);
+        if let Some(i) = self.balls.iter().position(|ball| ball.pos.distance(point) <= ball.radius) {
+            return BodyId::Ball(i);
+        }
+        if let Some(i) = self.blocks.iter().position(|block| !block.is_static() && inside(block.rect())) {
+            return BodyId::Block(i);
+        }
+        if let Some(i) = self.polygons.iter().position(|polygon| polygon.contains(point)) {
+            return BodyId::Polygon(i);
+        }
+        if inside(self.player.rect()) {
+            return BodyId::Player;
+        }
+        BodyId::Fixed
+    }
+
+    // center and angle of a body, alpha blends between the last two steps
+    // like the draw fns do
+    pub fn frame(&self, id: BodyId, alpha: f32) -> (raylib::Vector2, f32) {
+        let blend = |prev: f32, angle: f32| prev + (angle - prev) * alpha;
+        match id {
+            BodyId::Ball(i) => {
+                let ball = &self.balls[i];
+                (ball.prev_pos.lerp(ball.pos, alpha), blend(ball.prev_angle, ball.angle))
+            }
+            BodyId::Block(i) => {
+                let block = &self.blocks[i];
+                (block.prev_pos.lerp(block.pos, alpha) + block.dim / 2.0, 0.0)
+            }
+            BodyId::Polygon(i) => {
+                let polygon = &self.polygons[i];
+                (polygon.prev_pos.lerp(polygon.pos, alpha), blend(polygon.prev_angle, polygon.angle))
+            }
+            BodyId::Player => {
+                let player = self.player;
+                let pos = player.prev_pos.lerp(player.pos, alpha) + player.dim / 2.0;
+                (pos, blend(player.prev_angle, player.angle))
+            }
+            BodyId::Fixed => (raylib::Vector2::zero(), 0.0),
+        }
+    }
+
+    // where an anchor on id is in the world, in m
+    pub fn to_world(&self, id: BodyId, anchor: raylib::Vector2, alpha: f32) -> raylib::Vector2 {
+        let (center, angle) = self.frame(id, alpha);
+        center + anchor.rotate(angle)
+    }
+
+    // the anchor on id that's at point in the world right now
+    pub fn to_local(&self, id: BodyId, point: raylib::Vector2) -> raylib::Vector2 {
+        let (center, angle) = self.frame(id, 1.0);
+        (point - center).rotate(-angle)
+    }
+
+    // Hands both ends of a joint held by the world to whatever is at them,
+    // pins keep b in the world. None if both ends end up on the same body.
+    pub fn attach(&self, joint: Joint) -> Option<Joint> {
+        let (from, to) = (joint.anchor_a, joint.anchor_b);
+        let a = self.at(from);
+        let b = if joint.kind == JointKind::Pin { BodyId::Fixed } else { self.at(to) };
+        if a == b {
+            return None;
+        }
+        Some(Joint { a, b, anchor_a: self.to_local(a, from), anchor_b: self.to_local(b, to), ..joint })
+    }
+}
+
+// A chain of balls from one point to another, each held to the next by a
+// rope joint. Pinned ends are held by whatever is there, or the world.
+#[derive(Clone, Copy, Debug)]
+pub struct Rope {
+    pub from: raylib::Vector2, // in m
+    pub to: raylib::Vector2,   // in m
+    pub links: usize,          // balls, at least 2
+    pub radius: f32,           // in m
+    pub material: Material,
+    pub color: raylib::Color,
+    pub pin_from: bool,
+    pub pin_to: bool,
+}
+
+impl Rope {
+    // hangs from from, with as many links as fit
+    pub fn new(from: raylib::Vector2, to: raylib::Vector2) -> Self {
+        let links = (from.distance(to) / (ROPE_SPACING * ROPE_RADIUS)).round() as usize + 1;
+        Rope {
+            from,
+            to,
+            links: links.max(2),
+            radius: ROPE_RADIUS,
+            material: Material::RUBBER,
+            color: JointKind::Rope.color(),
+            pin_from: true,
+            pin_to: false,
+        }
+    }
+
+    // first is the index the first ball gets once they're added to bodies
+    pub fn build(&self, bodies: &Bodies, first: usize) -> (Vec<Ball>, Vec<Joint>) {
+        let last = self.links - 1;
+        let balls: Vec<Ball> = (0..self.links)
+            .map(|i| {
+                let pos = self.from.lerp(self.to, i as f32 / last as f32);
+                Ball { color: self.color, ..Ball::at(pos, self.radius, self.material) }
+            })
+            .collect();
+
+        let spacing = self.from.distance(self.to) / last as f32;
+        let zero = raylib::Vector2::zero();
+        let mut joints: Vec<Joint> = (0..last)
+            .map(|i| {
+                let mut link = Joint::new(JointKind::Rope, BodyId::Ball(first + i), zero, BodyId::Ball(first + i + 1), zero);
+                link.length = spacing;
+                link
+            })
+            .collect();
+
+        let ends = [(self.pin_from, self.from, first), (self.pin_to, self.to, first + last)];
+        for (_, point, ball) in ends.into_iter().filter(|&(pinned, _, _)| pinned) {
+            let held = bodies.at(point);
+            joints.push(Joint::new(JointKind::Pin, BodyId::Ball(ball), zero, held, bodies.to_local(held, point)));
+        }
+        (balls, joints)
+    }
+}
@@ -0,0 +1,29 @@
+// the unsafe fns in here are the ones drawing through raylib, they need an
+// open window and nothing else
+#![allow(clippy::missing_safety_doc)]
+
+pub mod ball;
+pub mod block;
+pub mod broadphase;
+pub mod ccd;
+pub mod collider;
+pub mod contact;
+pub mod field;
+pub mod fluid;
+pub mod hoop;
+pub mod integrator;
+pub mod joint;
+pub mod liquid;
+pub mod material;
+pub mod nbody;
+pub mod obj;
+pub mod player;
+pub mod polygon;
+pub mod replay;
+pub mod scene;
+pub mod sleep;
+pub mod snapshot;
+pub mod soft;
+pub mod solver;
+pub mod timestep;
+pub mod world;
@@ -0,0 +1,224 @@
+// Liquids are made of particles, small balls that collide with everything
+// balls do except each other. Position based fluids (Macklin and Müller)
+// keeps them apart instead: every step each particle is moved to where it's
+// going, then its neighbors within the smoothing length are pushed out of
+// the way until no particle has more of them around than at rest. Whatever
+// they were pushed by becomes velocity, that's the pressure. Viscosity
+// evens out the velocities of neighbors (XSPH).
+//
+// Densities here count particles per m², weighed by how close they are, so
+// the liquid's own density only sets how heavy its particles are and what
+// floats in it.
+
+use std::f32::consts::PI;
+
+use crate::ball::{Ball, PIXELS_PER_METER};
+use crate::broadphase::Broadphase;
+use crate::material::{Combine, Friction, Material};
+
+pub const LIQUID_SPACING: f32 = 0.2; // in m, between particles at rest
+pub const LIQUID_DENSITY: f32 = 0.27; // in kg/m², the same as Fluid::WATER
+pub const LIQUID_VISCOSITY: f32 = 0.05;
+pub const LIQUID_ITERATIONS: u32 = 4;
+pub const POUR_SIZE: f32 = 1.0; // in m, across what L pours
+
+// the smoothing length, in spacings
+const SMOOTHING: f32 = 2.0;
+// in 1/m², keeps the pushes small where a particle has few neighbors
+const RELAXATION: f32 = 50.0;
+
+// doesn't bounce, slides along what it touches
+const PARTICLE: Material = Material {
+    restitution: 0.0,
+    friction: Friction::new(0.1, 0.05, 0.0),
+    density: LIQUID_DENSITY,
+    restitution_combine: Combine::Min,
+    friction_combine: Combine::Min,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Liquid {
+    pub particles: Vec<usize>, // into the world's balls
+    pub spacing: f32,          // in m, between particles at rest
+    pub density: f32,          // in kg/m², like the materials
+    // how much of the difference to their neighbors' velocity particles
+    // lose every step, 0 to 1
+    pub viscosity: f32,
+    pub color: raylib::Color,
+    pub densities: Vec<f32>, // of every particle last step, 1 at rest, for drawing
+}
+
+impl Liquid {
+    // water without any particles yet, see pour
+    pub fn new() -> Self {
+        Liquid {
+            particles: Vec::new(),
+            spacing: LIQUID_SPACING,
+            density: LIQUID_DENSITY,
+            viscosity: LIQUID_VISCOSITY,
+            color: raylib::BLUE,
+            densities: Vec::new(),
+        }
+    }
+
+    // Fills rect (in m) with particles at rest, first is the index the first
+    // of them gets once they're added to the balls.
+    pub fn pour(&mut self, rect: raylib::Rectangle, first: usize) -> Vec<Ball> {
+        let columns = (rect.width / self.spacing).floor().max(1.0) as usize;
+        let rows = (rect.height / self.spacing).floor().max(1.0) as usize;
+        // centered in rect, half a spacing from its edges at most
+        let corner = raylib::Vector2::new(
+            rect.x + (rect.width - (columns - 1) as f32 * self.spacing) / 2.0,
+            rect.y + (rect.height - (rows - 1) as f32 * self.spacing) / 2.0,
+        );
+        let balls: Vec<Ball> = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let pos = corner + raylib::Vector2::new(column as f32, row as f32) * self.spacing;
+                let material = Material { density: self.density, ..PARTICLE };
+                // as heavy as the square of liquid around it
+                let mass = self.density * self.spacing * self.spacing;
+                Ball { mass, color: self.color, ..Ball::at(pos, self.spacing / 2.0, material) }
+            })
+            .collect();
+        self.particles.extend(first..first + balls.len());
+        self.densities.resize(self.particles.len(), 1.0);
+        balls
+    }
+
+    // Changes the particles' velocities so they end up apart after dt, before
+    // the solver sees them. Neighbors come from the broadphase, grown to the
+    // smoothing length.
+    pub fn apply(&mut self, balls: &mut [Ball], broadphase: &mut Broadphase, dt: f32) {
+        if self.particles.is_empty() {
+            return;
+        }
+        let h = self.spacing * SMOOTHING;
+        let rest = rest_density(self.spacing, h);
+        let n = self.particles.len();
+
+        // where every particle goes if nothing pushes it
+        let mut predicted: Vec<Ball> = self
+            .particles
+            .iter()
+            .map(|&i| Ball { pos: balls[i].pos + balls[i].velocity * dt + balls[i].drift, ..balls[i] })
+            .collect();
+        let pairs = broadphase.find_pairs_within(&predicted, h / 2.0 - self.spacing / 2.0);
+
+        let mut density = vec![0.0; n];
+        let mut lambda = vec![0.0; n];
+        let mut gradient = vec![raylib::Vector2::zero(); n];
+        let mut squared = vec![0.0; n];
+        let mut delta = vec![raylib::Vector2::zero(); n];
+        for _ in 0..LIQUID_ITERATIONS {
+            density.fill(poly6(0.0, h));
+            gradient.fill(raylib::Vector2::zero());
+            squared.fill(0.0);
+            for &(i, j) in pairs {
+                let offset = predicted[i].pos - predicted[j].pos;
+                let w = poly6(offset.length_squared(), h);
+                density[i] += w;
+                density[j] += w;
+                let g = spiky(offset, h) / rest;
+                gradient[i] += g;
+                gradient[j] -= g;
+                squared[i] += g.dot(g);
+                squared[j] += g.dot(g);
+            }
+
+            // only ever pushes apart, a liquid with room around it spreads out
+            for k in 0..n {
+                let compression = (density[k] / rest - 1.0).max(0.0);
+                lambda[k] = -compression / (squared[k] + gradient[k].dot(gradient[k]) + RELAXATION);
+            }
+            delta.fill(raylib::Vector2::zero());
+            for &(i, j) in pairs {
+                let push = spiky(predicted[i].pos - predicted[j].pos, h) * ((lambda[i] + lambda[j]) / rest);
+                delta[i] += push;
+                delta[j] -= push;
+            }
+            for (particle, delta) in predicted.iter_mut().zip(delta.iter()) {
+                if !particle.sleep.is_asleep() {
+                    particle.pos += *delta;
+                }
+            }
+        }
+
+        for (particle, &i) in predicted.iter_mut().zip(self.particles.iter()) {
+            let ball = &mut balls[i];
+            if !ball.sleep.is_asleep() {
+                ball.velocity = (particle.pos - ball.pos - ball.drift) / dt;
+                particle.velocity = ball.velocity;
+            }
+        }
+
+        // each particle loses some of the difference to its neighbors, weighed
+        // so the weights of all of them add up to about one
+        delta.fill(raylib::Vector2::zero());
+        for &(i, j) in pairs {
+            let w = poly6((predicted[i].pos - predicted[j].pos).length_squared(), h);
+            let difference = predicted[j].velocity - predicted[i].velocity;
+            delta[i] += difference * (w / density[j]);
+            delta[j] -= difference * (w / density[i]);
+        }
+        for (delta, &i) in delta.into_iter().zip(self.particles.iter()) {
+            if !balls[i].sleep.is_asleep() {
+                balls[i].velocity += delta * self.viscosity;
+            }
+        }
+
+        self.densities = density.into_iter().map(|density| density / rest).collect();
+    }
+
+    // Every particle as a disc that overlaps its neighbors' so they run
+    // together, paler where they're spread thin like foam.
+    pub unsafe fn draw(&self, balls: &[Ball], alpha: f32) {
+        for (&i, &density) in self.particles.iter().zip(self.densities.iter()) {
+            let ball = &balls[i];
+            let pos = ball.prev_pos.lerp(ball.pos, alpha) * PIXELS_PER_METER;
+            let foam = (1.0 - density).clamp(0.0, 1.0);
+            raylib::draw_circle_v(pos, self.spacing * PIXELS_PER_METER, mix(self.color, raylib::RAYWHITE, foam));
+        }
+    }
+}
+
+impl Default for Liquid {
+    fn default() -> Self {
+        Liquid::new()
+    }
+}
+
+// in 1/m², how much a neighbor r_squared (in m²) away counts towards the
+// density, zero from h on
+fn poly6(r_squared: f32, h: f32) -> f32 {
+    let left = (h * h - r_squared).max(0.0);
+    4.0 / (PI * h.powi(8)) * left * left * left
+}
+
+// in 1/m³, which way a neighbor at -offset counts for more, towards it. the
+// spiky kernel's, which doesn't flatten out when they get close
+fn spiky(offset: raylib::Vector2, h: f32) -> raylib::Vector2 {
+    let r = offset.length();
+    if r <= 0.0 || r >= h {
+        return raylib::Vector2::zero();
+    }
+    offset * (-30.0 / (PI * h.powi(5)) * (h - r) * (h - r) / r)
+}
+
+// in 1/m², the density of particles in a square grid spacing apart
+fn rest_density(spacing: f32, h: f32) -> f32 {
+    let reach = (h / spacing).ceil() as i32;
+    let mut density = 0.0;
+    for row in -reach..=reach {
+        for column in -reach..=reach {
+            let offset = raylib::Vector2::new(column as f32, row as f32) * spacing;
+            density += poly6(offset.length_squared(), h);
+        }
+    }
+    density
+}
+
+fn mix(a: raylib::Color, b: raylib::Color, t: f32) -> raylib::Color {
+    let channel = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * t).round() as u8;
+    raylib::Color { r: channel(a.r, b.r), g: channel(a.g, b.g), b: channel(a.b, b.b), a: a.a }
+}
@@ -1,12 +1,20 @@
-use raylib;
+use aid::Ternary;
+use physim::ball::{Ball, PIXELS_PER_METER};
+use physim::block::Block;
+use physim::field::{Field, FieldKind};
+use physim::joint::JointKind;
+use physim::liquid::POUR_SIZE;
+use physim::player::PlayerInput;
+use physim::polygon::Polygon;
+use physim::replay::{Playback, Recorder, Replay, REPLAY_EXTENSION};
+use physim::scene::Scene;
+use physim::snapshot::{Format, Snapshot};
+use physim::soft::{Lattice, LatticeKind};
+use physim::world::World;
+use std::env;
 use std::ffi::c_void;
-use std::hint;
-mod ball;
-use crate::ball::*;
-mod player;
-use crate::player::*;
-mod obj;
-use crate::obj::*;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[cfg(target_family = "wasm")]
 use libc::emscripten_set_main_loop_arg;
@@ -18,11 +26,15 @@ const GHOSTTY: raylib::Color = raylib::Color {
     a: 255,
 };
 
-unsafe fn draw_grid(screen: raylib::Vector2) {
-    let width: i32 = (screen.x / PIXELS_PER_METER).ceil() as i32;
-    let height: i32 = (screen.y / PIXELS_PER_METER).ceil() as i32;
+// what sleeping bodies are drawn in while KeyZ shows them
+const ASLEEP: raylib::Color = raylib::DARKBLUE;
 
-    for i in 0..(width * height) as i32 {
+// bounds in m
+unsafe fn draw_grid(bounds: raylib::Vector2) {
+    let width: i32 = bounds.x.ceil() as i32;
+    let height: i32 = bounds.y.ceil() as i32;
+
+    for i in 0..(width * height) {
         let x: i32 = i % width;
         let y: i32 = i / width;
 
@@ -39,124 +51,463 @@ unsafe fn draw_grid(screen: raylib::Vector2) {
     }
 }
 
-pub struct Hoop {
-    pub pos: raylib::Vector2,      // in m
-    pub dim: raylib::Vector2,      // in m
-    pub color: raylib::Color,
+fn random_seed() -> u64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(time) => time.as_nanos() as u64 % 1_000_000,
+        Err(_) => 0,
+    }
+}
+
+// the value following `name` on the command line
+fn arg_value(name: &str) -> Option<String> {
+    let mut args = env::args().skip_while(|arg| arg != name);
+    args.next()?;
+    args.next()
 }
 
-impl Hoop {
-    unsafe fn draw(&self) {
-        raylib::draw_rectangle(self.pos.x as i32, self.pos.y as i32, self.dim.x as i32, self.dim.y as i32, self.color);
+// `--seed <n>` on the command line, a fresh one otherwise
+fn parse_seed() -> u64 {
+    match arg_value("--seed").map(|arg| arg.parse::<u64>()) {
+        Some(Ok(seed)) => seed,
+        Some(Err(_)) => {
+            eprintln!("--seed expects a non-negative integer, using a random seed");
+            random_seed()
+        }
+        None => random_seed(),
     }
+}
 
-    fn update(&mut self, screen: raylib::Vector2, balls: &mut Vec<Ball>) {
-        let x = screen.x - self.dim.x;
-        let y = screen.y / 2.0 - (self.dim.y / 2.0);
+// the file picker in assets/template.html writes the chosen scene here
+#[cfg(target_family = "wasm")]
+const PENDING_SCENE: &str = "/pending_scene.toml";
+
+fn load_scene_file(game: &mut Game, path: &str) {
+    match Scene::load(path) {
+        Ok(scene) => {
+            interrupt(game);
+            game.world.load_scene(scene);
+            game.message = None;
+            println!("Loaded {path}, seed {}", game.world.seed);
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            game.message = Some(err);
+        }
+    }
+}
 
-        self.pos.x = x;
-        self.pos.y = y;
+const SNAPSHOT_DIR: &str = "snapshots";
+const SLOT_KEYS: [raylib::KeyboardKey; 4] = [
+    raylib::KeyboardKey::KeyOne,
+    raylib::KeyboardKey::KeyTwo,
+    raylib::KeyboardKey::KeyThree,
+    raylib::KeyboardKey::KeyFour,
+];
 
-        unsafe {
-            let rect = raylib::Rectangle{ x: self.pos.x, y: self.pos.y,
-                width: self.dim.x, height: self.dim.y };
+fn slot_path(slot: usize, format: Format) -> PathBuf {
+    Path::new(SNAPSHOT_DIR).join(format!("slot{slot}.{}", format.extension()))
+}
 
-            let mut i = 0;
-            let mut cond = balls.len();
-            while i < cond {
-                if raylib::check_collision_circle_rec(balls[i].pos, balls[i].radius, rect) {
-                    balls.remove(i);
-                    cond -= 1;
-                    i += 1;
-                    println!("Removed Ball Number {i}");
-                    continue;
-                }
-                i += 1;
-            }
+// the binary file is what gets loaded, the text one is there to be read
+fn quick_save(game: &mut Game) {
+    let snapshot = Snapshot::capture(&game.world);
+    for format in [Format::Binary, Format::Text] {
+        if let Err(err) = snapshot.save(&slot_path(game.slot, format), format) {
+            eprintln!("{err}");
+            game.message = Some(err);
+            return;
         }
     }
+    println!("Saved slot {} at tick {}", game.slot, game.world.tick);
+}
 
-    fn new() -> Hoop {
-        let dim = raylib::Vector2{ x: 1.0 * PIXELS_PER_METER, y: 2.0 * PIXELS_PER_METER };
-        Hoop {
-            pos: unsafe { raylib::vector_2_zero() },
-            dim,
-            color: raylib::RAYWHITE
+fn load_snapshot_file(game: &mut Game, path: &Path) {
+    match Snapshot::load(path) {
+        Ok(snapshot) => {
+            interrupt(game);
+            snapshot.restore(&mut game.world);
+            game.message = None;
+            println!("Loaded {} at tick {}", path.display(), game.world.tick);
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            game.message = Some(err);
         }
     }
 }
 
-impl Collision for Hoop {
-    fn collision_with_ball(&mut self, ball: &mut Ball) {
-        unsafe {
-            if !raylib::check_collision_circle_rec(
-                ball.pos, ball.radius,
-                raylib::Rectangle{ x: self.pos.x, y: self.pos.y, width: self.dim.x, height: self.dim.y }) {
-                return;
-            }
+const REPLAY_DIR: &str = "replays";
+
+fn start_recording(game: &mut Game) {
+    game.recorder = Some(Recorder::start(&game.world));
+    println!("Recording from tick {}", game.world.tick);
+}
+
+// saves to `--record <path>` if given, a new file in replays/ otherwise
+fn stop_recording(game: &mut Game) {
+    let Some(recorder) = game.recorder.take() else {
+        return;
+    };
+    let replay = recorder.replay;
+    let path = match arg_value("--record") {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let time = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+            let name = format!("seed{}-{time}.{REPLAY_EXTENSION}", replay.seed());
+            Path::new(REPLAY_DIR).join(name)
+        }
+    };
+    match replay.save(&path) {
+        Ok(()) => println!("Saved {} steps to {}", replay.len(), path.display()),
+        Err(err) => {
+            eprintln!("{err}");
+            game.message = Some(err);
         }
     }
+    game.last_replay = Some(replay);
+}
+
+fn play_replay(game: &mut Game, replay: Replay) {
+    stop_recording(game);
+    println!("Replaying {} steps, seed {}", replay.len(), replay.seed());
+    game.playback = Some(Playback::start(replay, &mut game.world));
+}
 
-    fn collision_with_rect<T>(rect: &mut T) {
-        todo!()
+fn load_replay_file(game: &mut Game, path: &Path) {
+    match Replay::load(path) {
+        Ok(replay) => {
+            game.message = None;
+            play_replay(game, replay);
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            game.message = Some(err);
+        }
     }
 }
 
+// anything that swaps out the world's state ends the recording or replay,
+// neither would match what happens afterwards
+fn interrupt(game: &mut Game) {
+    stop_recording(game);
+    game.playback = None;
+}
+
+unsafe fn poll_playback_keys(game: &mut Game) {
+    let Some(playback) = &mut game.playback else {
+        return;
+    };
+    let world = &mut game.world;
+    let seek = raylib::is_key_down(raylib::KeyboardKey::KeyLeftShift).ternary(10.0, 1.0);
+
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyP) {
+        playback.paused = !playback.paused;
+    }
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyLeft) {
+        playback.seek_by(world, -seek);
+    }
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyRight) {
+        playback.seek_by(world, seek);
+    }
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyUp) {
+        playback.faster();
+    }
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyDown) {
+        playback.slower();
+    }
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyPeriod) && playback.paused {
+        playback.step(world);
+    }
+}
+
+unsafe fn poll_input() -> PlayerInput {
+    PlayerInput {
+        left: raylib::is_key_down(raylib::KeyboardKey::KeyA),
+        right: raylib::is_key_down(raylib::KeyboardKey::KeyD),
+        jump: raylib::is_key_pressed(raylib::KeyboardKey::KeyW),
+        slam: raylib::is_key_pressed(raylib::KeyboardKey::KeyS),
+    }
+}
 
 unsafe extern "C" fn game_loop(game_void: *mut c_void) {
-    let game: *mut Game = game_void as *mut Game;
-    let player: *mut Player = &mut (*game).player;
-    let hoop: *mut Hoop = &mut (*game).hoop;
-    let balls: *mut Vec<Ball> = &mut (*game).balls;
+    let game: &mut Game = &mut *(game_void as *mut Game);
+
+    for path in raylib::take_dropped_files() {
+        if Snapshot::is_snapshot(Path::new(&path)) {
+            load_snapshot_file(game, Path::new(&path));
+        } else if path.ends_with(REPLAY_EXTENSION) {
+            load_replay_file(game, Path::new(&path));
+        } else {
+            load_scene_file(game, &path);
+        }
+    }
+    #[cfg(target_family = "wasm")]
+    if std::path::Path::new(PENDING_SCENE).exists() {
+        load_scene_file(game, PENDING_SCENE);
+        let _ = std::fs::remove_file(PENDING_SCENE);
+    }
 
-    let dt = raylib::get_frame_time();
+    for (slot, key) in SLOT_KEYS.into_iter().enumerate() {
+        if raylib::is_key_pressed(key) {
+            game.slot = slot + 1;
+        }
+    }
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyF5) {
+        quick_save(game);
+    }
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyF9) {
+        load_snapshot_file(game, &slot_path(game.slot, Format::Binary));
+    }
 
     if raylib::is_key_pressed(raylib::KeyboardKey::KeyR) {
-        (*game).balls = Ball::new_vec(NUM_OF_BALLS, (*game).screen);
-        (*game).player = Player::new();
+        interrupt(game);
+        // shift + R rolls a new seed, plain R replays the current one
+        let seed = raylib::is_key_down(raylib::KeyboardKey::KeyLeftShift)
+            .ternary(random_seed(), game.world.seed);
+        game.world.reset(seed);
+        println!("Seed {seed}");
     }
-    if raylib::is_key_pressed(raylib::KeyboardKey::KeyGrave) {
-        (*game).is_showing_background = !(*game).is_showing_background;
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyF2) {
+        if game.recorder.is_some() {
+            stop_recording(game);
+        } else {
+            game.playback = None;
+            start_recording(game);
+        }
+    }
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyF3) {
+        // stopping a replay keeps the world where it is and hands it back
+        if game.playback.take().is_none() {
+            stop_recording(game);
+            if let Some(replay) = game.last_replay.clone() {
+                play_replay(game, replay);
+            }
+        }
+    }
+    poll_playback_keys(game);
+
+    // M picks a material, clicking on something gives it that material. not
+    // while recording or replaying, replays only know about the input
+    let materials = &game.world.scene.materials;
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyM) {
+        game.brush = (game.brush + 1) % materials.len();
+    }
+    let is_live = game.recorder.is_none() && game.playback.is_none();
+    if is_live && raylib::is_mouse_button_pressed(raylib::MouseButton::MouseButtonLeft) {
+        let (_, material) = materials.nth(game.brush);
+        let point = raylib::get_mouse_position() / PIXELS_PER_METER;
+        game.world.set_material_at(point, material);
+    }
+
+    // J picks a joint, right clicking twice joins what's under both clicks,
+    // once for a pin. ropes hang from the first click to the second
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyJ) {
+        game.joint_kind = game.joint_kind.next();
+        game.joint_from = None;
+    }
+    if is_live && raylib::is_mouse_button_pressed(raylib::MouseButton::MouseButtonRight) {
+        let point = raylib::get_mouse_position() / PIXELS_PER_METER;
+        match game.joint_from.take() {
+            None if game.joint_kind != JointKind::Pin => game.joint_from = Some(point),
+            from => {
+                game.world.add_joint_at(game.joint_kind, from.unwrap_or(point), point);
+            }
+        }
+    }
+    if !is_live {
+        game.joint_from = None;
+    }
+
+    // I picks how bodies move between steps, see integrator.rs
+    if is_live && raylib::is_key_pressed(raylib::KeyboardKey::KeyI) {
+        game.world.integrator = game.world.integrator.next();
     }
 
-    if raylib::is_window_resized() {
-        (*game).screen = raylib::get_screen_dimensions();
+    // N turns balls pulling on each other on and off, see nbody.rs
+    if is_live && raylib::is_key_pressed(raylib::KeyboardKey::KeyN) {
+        game.world.toggle_gravitation();
     }
-    for i in 0..(*game).balls.len() {
-        (*game).balls[i].update((*game).screen, dt);
-        update_ball_to_ball_collision(i, &mut (*balls));
-        (*player).update_collision_with_balls(&mut (*balls));
+
+    // G drops a blob under the mouse, with shift a block of jelly
+    if is_live && raylib::is_key_pressed(raylib::KeyboardKey::KeyG) {
+        let point = raylib::get_mouse_position() / PIXELS_PER_METER;
+        let kind = raylib::is_key_down(raylib::KeyboardKey::KeyLeftShift).ternary(LatticeKind::Grid, LatticeKind::Ring);
+        game.world.add_soft_body(Lattice::at(kind, point));
     }
 
-    (*player).update((*game).screen, dt);
-    (*hoop).update((*game).screen, &mut (*balls));
+    // L pours water under the mouse, or more of the scene's first liquid
+    if is_live && raylib::is_key_pressed(raylib::KeyboardKey::KeyL) {
+        let point = raylib::get_mouse_position() / PIXELS_PER_METER - raylib::Vector2::new_from(POUR_SIZE / 2.0);
+        game.world.pour(raylib::Rectangle { x: point.x, y: point.y, width: POUR_SIZE, height: POUR_SIZE });
+    }
+
+    // F picks a field, middle clicking puts one there, with shift it pushes
+    // the other way. backspace takes the last one away
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyF) {
+        game.field_kind = game.field_kind.next();
+    }
+    if is_live && raylib::is_mouse_button_pressed(raylib::MouseButton::MouseButtonMiddle) {
+        let point = raylib::get_mouse_position() / PIXELS_PER_METER;
+        let negative = raylib::is_key_down(raylib::KeyboardKey::KeyLeftShift);
+        game.world.add_field(Field::at(game.field_kind, point, negative));
+    }
+    if is_live && raylib::is_key_pressed(raylib::KeyboardKey::KeyBackspace) {
+        game.world.remove_field();
+    }
+
+    let world: &mut World = &mut game.world;
+
+    let frame_time = raylib::get_frame_time();
+
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyB) {
+        world.broadphase.kind = world.broadphase.kind.next();
+    }
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyGrave) {
+        game.is_showing_background = !game.is_showing_background;
+    }
+    if raylib::is_key_pressed(raylib::KeyboardKey::KeyZ) {
+        game.is_showing_sleep = !game.is_showing_sleep;
+    }
+
+    // resizing the window only changes how much of the world is visible,
+    // a world with other bounds resizes the window to fit
+    if world.bounds != game.window_bounds {
+        let screen = world.bounds * PIXELS_PER_METER;
+        raylib::set_window_size(screen.x as i32, screen.y as i32);
+        game.window_bounds = world.bounds;
+    }
+
+    let alpha = match (&mut game.playback, &mut game.recorder) {
+        (Some(playback), _) => playback.advance(world, frame_time),
+        (None, Some(recorder)) => {
+            world.set_input(poll_input());
+            recorder.advance(world, frame_time)
+        }
+        (None, None) => {
+            world.set_input(poll_input());
+            world.advance(frame_time)
+        }
+    };
 
     raylib::begin_drawing();
     raylib::clear_background(GHOSTTY);
 
-    if (*game).is_showing_background {
-        draw_grid((*game).screen);
+    if game.is_showing_background {
+        draw_grid(world.bounds);
     }
 
-    for i in 0..(*balls).len() {
-        (*balls)[i].draw(i);
+    for field in world.fields.iter() {
+        field.draw();
     }
 
-    (*hoop).draw();
+    for soft in world.soft_bodies.iter() {
+        soft.draw(&world.balls, alpha);
+    }
+
+    // liquids draw their own particles
+    let liquid_of = world.liquid_of();
+    for (i, ball) in world.balls.iter().enumerate().filter(|&(i, _)| liquid_of[i].is_none()) {
+        if game.is_showing_sleep && ball.sleep.is_asleep() {
+            Ball { color: ASLEEP, ..*ball }.draw(i, alpha);
+        } else {
+            ball.draw(i, alpha);
+        }
+    }
 
-    (*player).draw();
+    // their particles as blobs that run together, after the other balls
+    for liquid in world.liquids.iter() {
+        liquid.draw(&world.balls, alpha);
+    }
+
+    // over the balls, so the ones in it look under water
+    for pool in world.pools.iter() {
+        pool.draw();
+    }
+
+    for collider in world.colliders.iter() {
+        collider.draw();
+    }
+
+    for block in world.blocks.iter() {
+        if game.is_showing_sleep && block.sleep.is_asleep() {
+            Block { color: ASLEEP, ..*block }.draw(alpha);
+        } else {
+            block.draw(alpha);
+        }
+    }
+
+    for polygon in world.polygons.iter() {
+        if game.is_showing_sleep && polygon.sleep.is_asleep() {
+            Polygon { color: ASLEEP, ..polygon.clone() }.draw(alpha);
+        } else {
+            polygon.draw(alpha);
+        }
+    }
+
+    for hoop in world.hoops.iter() {
+        hoop.draw();
+    }
+
+    let bodies = world.bodies();
+    for joint in world.joints.iter() {
+        let a = bodies.to_world(joint.a, joint.anchor_a, alpha);
+        let b = bodies.to_world(joint.b, joint.anchor_b, alpha);
+        joint.draw(a, b);
+    }
+    if let Some(from) = game.joint_from {
+        let mouse = raylib::get_mouse_position();
+        raylib::draw_line_v(from * PIXELS_PER_METER, mouse, game.joint_kind.color());
+    }
+
+    world.player.draw(alpha);
 
     raylib::draw_fps(0, 0);
+    raylib::draw_text(&format!("seed {}", world.seed), 0, 20, 20, raylib::LIME);
+    raylib::draw_text(world.broadphase.kind.name(), 0, 40, 20, raylib::LIME);
+    raylib::draw_text(&format!("slot {}", game.slot), 0, 60, 20, raylib::LIME);
+    let (brush, _) = world.scene.materials.nth(game.brush);
+    raylib::draw_text(&format!("material {brush}"), 0, 80, 20, raylib::LIME);
+    raylib::draw_text(&format!("joint {}", game.joint_kind.name()), 0, 100, 20, raylib::LIME);
+    raylib::draw_text(&format!("integrator {}", world.integrator.name()), 0, 120, 20, raylib::LIME);
+    raylib::draw_text(&format!("field {}", game.field_kind.name()), 0, 140, 20, raylib::LIME);
+    if let Some(playback) = &game.playback {
+        let state = playback.paused.ternary("paused", "playing");
+        let progress = format!(
+            "replay {}/{} {state} x{}",
+            playback.position(world),
+            playback.replay.len(),
+            playback.speed()
+        );
+        raylib::draw_text(&progress, 0, 160, 20, raylib::SKYBLUE);
+    } else if let Some(recorder) = &game.recorder {
+        let progress = format!("recording {}", recorder.replay.len());
+        raylib::draw_text(&progress, 0, 160, 20, raylib::RED);
+    }
+    if game.is_showing_sleep {
+        let asleep = format!("asleep {}", world.asleep_count());
+        raylib::draw_text(&asleep, 0, 180, 20, raylib::SKYBLUE);
+    }
+    if let Some(message) = &game.message {
+        raylib::draw_text(message, 0, raylib::get_screen_height() - 20, 20, raylib::RED);
+    }
 
     raylib::end_drawing();
 }
 
 struct Game {
-    screen: raylib::Vector2,
-    balls: Vec<Ball>,
+    world: World,
     is_showing_background: bool,
-    player: Player,
-    hoop: Hoop
+    is_showing_sleep: bool,              // sleeping bodies in their own color, KeyZ
+    message: Option<String>,             // last scene or snapshot error, shown at the bottom
+    slot: usize,                         // quick-save slot, 1 to 4
+    recorder: Option<Recorder>,
+    playback: Option<Playback>,
+    last_replay: Option<Replay>,         // what F3 plays
+    brush: usize,                        // material clicks assign, see Materials::nth
+    joint_kind: JointKind,               // what right clicks make
+    joint_from: Option<raylib::Vector2>, // in m, the first click of a joint
+    field_kind: FieldKind,               // what middle clicks make
+    window_bounds: raylib::Vector2,      // the world bounds the window was sized for
 }
 
 fn main() {
@@ -165,16 +516,59 @@ fn main() {
             raylib::ConfigFlags::FlagWindowResizable | raylib::ConfigFlags::FlagMsaa4xHint,
         );
         raylib::set_target_fps(120);
-        let screen = raylib::Vector2 { x: 800.0, y: 600.0 };
+
+        let mut scene = match arg_value("--scene") {
+            Some(path) => Scene::load(&path).unwrap_or_else(|err| {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }),
+            None => Scene::default(),
+        };
+        if let Some(num_balls) = arg_value("--balls").and_then(|arg| arg.parse().ok()) {
+            scene.random_balls = num_balls;
+        }
+        match arg_value("--hz").map(|arg| arg.parse::<f32>()) {
+            Some(Ok(hz)) if hz > 0.0 && hz.is_finite() => scene.hz = hz,
+            Some(_) => eprintln!("--hz expects a positive number, using {}", scene.hz),
+            None => {}
+        }
+
+        let bounds = scene.bounds;
+        let screen = bounds * PIXELS_PER_METER;
         raylib::init_window(screen.x as i32, screen.y as i32, "PhySim");
 
-        let game = Box::new(Game {
-            screen,
-            balls: Ball::new_vec(NUM_OF_BALLS, screen),
+        // without a window nobody can close it, so stop after `--frames <n>`
+        #[cfg(feature = "headless")]
+        if let Some(frames) = arg_value("--frames").and_then(|arg| arg.parse().ok()) {
+            raylib::set_frame_limit(frames);
+        }
+
+        let seed = match (arg_value("--seed"), scene.seed) {
+            (None, Some(seed)) => seed,
+            _ => parse_seed(),
+        };
+        println!("Seed {seed}");
+
+        let mut game = Box::new(Game {
+            world: World::new(seed, scene),
             is_showing_background: true,
-            player: Player::new(),
-            hoop: Hoop::new()
+            is_showing_sleep: false,
+            message: None,
+            slot: 1,
+            recorder: None,
+            playback: None,
+            last_replay: None,
+            brush: 0,
+            joint_kind: JointKind::Distance,
+            joint_from: None,
+            field_kind: FieldKind::Point,
+            window_bounds: bounds,
         });
+        if let Some(path) = arg_value("--replay") {
+            load_replay_file(&mut game, Path::new(&path));
+        } else if arg_value("--record").is_some() {
+            start_recording(&mut game);
+        }
         let p_game = Box::into_raw(game) as *mut c_void;
 
         #[cfg(target_family = "wasm")]
@@ -185,6 +579,10 @@ fn main() {
             game_loop(p_game);
         }
 
-        drop(Box::from_raw(p_game));
+        let mut game = Box::from_raw(p_game as *mut Game);
+        stop_recording(&mut game);
+        #[cfg(feature = "headless")]
+        println!("{} balls left", game.world.balls.len());
+        drop(game);
     }
 }
@@ -0,0 +1,197 @@
+// What bodies are made of. Two touching bodies each bring their own
+// material, the combine rules decide what the contact uses: the rule with
+// the higher priority wins, so a Max material stays bouncy against anything.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Combine {
+    // lowest priority first
+    Average,
+    GeometricMean,
+    Min,
+    Multiply,
+    Max,
+}
+
+impl Combine {
+    pub const ALL: [Combine; 5] = [
+        Combine::Average,
+        Combine::GeometricMean,
+        Combine::Min,
+        Combine::Multiply,
+        Combine::Max,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Combine::Average => "average",
+            Combine::GeometricMean => "geometric_mean",
+            Combine::Min => "min",
+            Combine::Multiply => "multiply",
+            Combine::Max => "max",
+        }
+    }
+
+    pub fn apply(self, a: f32, b: f32) -> f32 {
+        match self {
+            Combine::Average => (a + b) / 2.0,
+            Combine::GeometricMean => (a * b).sqrt(),
+            Combine::Min => a.min(b),
+            Combine::Multiply => a * b,
+            Combine::Max => a.max(b),
+        }
+    }
+}
+
+// Coulomb friction. A contact sticks as long as the tangential impulse
+// needed to stop it sliding stays below static_coef times the normal
+// impulse, past that it slides with dynamic_coef. rolling is in m, how far
+// ahead of a rolling body the normal force acts, it slows the spin down.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Friction {
+    pub static_coef: f32,
+    pub dynamic_coef: f32,
+    pub rolling: f32,
+}
+
+impl Friction {
+    pub const fn new(static_coef: f32, dynamic_coef: f32, rolling: f32) -> Self {
+        Friction { static_coef, dynamic_coef, rolling }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Material {
+    pub restitution: f32, // 0 stops dead, 1 bounces back at full speed
+    pub friction: Friction,
+    pub density: f32, // in kg/m², the world is flat
+    pub restitution_combine: Combine,
+    pub friction_combine: Combine,
+}
+
+impl Material {
+    pub const RUBBER: Material = Material {
+        restitution: 0.9,
+        friction: Friction::new(0.9, 0.8, 0.02),
+        density: 0.25,
+        restitution_combine: Combine::Average,
+        friction_combine: Combine::GeometricMean,
+    };
+    pub const STEEL: Material = Material {
+        restitution: 0.6,
+        friction: Friction::new(0.6, 0.4, 0.005),
+        density: 1.8,
+        restitution_combine: Combine::Average,
+        friction_combine: Combine::GeometricMean,
+    };
+    pub const WOOD: Material = Material {
+        restitution: 0.5,
+        friction: Friction::new(0.6, 0.5, 0.01),
+        density: 0.15,
+        restitution_combine: Combine::Average,
+        friction_combine: Combine::GeometricMean,
+    };
+    // slippery against anything
+    pub const ICE: Material = Material {
+        restitution: 0.1,
+        friction: Friction::new(0.05, 0.03, 0.0),
+        density: 0.23,
+        restitution_combine: Combine::Average,
+        friction_combine: Combine::Min,
+    };
+    pub const STONE: Material = Material {
+        restitution: 0.7,
+        friction: Friction::new(0.7, 0.6, 0.0),
+        density: 0.6,
+        restitution_combine: Combine::Average,
+        friction_combine: Combine::GeometricMean,
+    };
+    // bouncy against anything
+    pub const SUPERBALL: Material = Material {
+        restitution: 0.98,
+        friction: Friction::new(0.9, 0.8, 0.01),
+        density: 0.3,
+        restitution_combine: Combine::Max,
+        friction_combine: Combine::GeometricMean,
+    };
+
+    pub const BUILTIN: [(&'static str, Material); 6] = [
+        ("rubber", Material::RUBBER),
+        ("steel", Material::STEEL),
+        ("wood", Material::WOOD),
+        ("ice", Material::ICE),
+        ("stone", Material::STONE),
+        ("superball", Material::SUPERBALL),
+    ];
+
+    pub fn builtin(name: &str) -> Option<Material> {
+        Material::BUILTIN
+            .into_iter()
+            .find(|&(builtin, _)| builtin == name)
+            .map(|(_, material)| material)
+    }
+
+    // (restitution, friction) of a contact between self and other
+    pub fn combine(self, other: Material) -> (f32, Friction) {
+        let restitution = self.restitution_combine.max(other.restitution_combine);
+        let friction = self.friction_combine.max(other.friction_combine);
+        (
+            restitution.apply(self.restitution, other.restitution),
+            Friction {
+                static_coef: friction.apply(self.friction.static_coef, other.friction.static_coef),
+                dynamic_coef: friction.apply(self.friction.dynamic_coef, other.friction.dynamic_coef),
+                rolling: self.friction.rolling.max(other.friction.rolling),
+            },
+        )
+    }
+}
+
+// Built in materials plus the ones a scene defines, by name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Materials {
+    pub custom: Vec<(String, Material)>,
+}
+
+impl Materials {
+    pub fn new() -> Self {
+        Materials { custom: Vec::new() }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Material> {
+        let custom = self.custom.iter().find(|(custom, _)| custom == name);
+        custom.map(|&(_, material)| material).or_else(|| Material::builtin(name))
+    }
+
+    pub fn len(&self) -> usize {
+        Material::BUILTIN.len() + self.custom.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // built in ones first, wraps around
+    pub fn nth(&self, index: usize) -> (&str, Material) {
+        let index = index % self.len();
+        match Material::BUILTIN.get(index) {
+            Some(&(name, material)) => (name, material),
+            None => {
+                let (name, material) = &self.custom[index - Material::BUILTIN.len()];
+                (name, *material)
+            }
+        }
+    }
+
+    // what a body's material is called, None if it was tweaked by hand
+    pub fn name_of(&self, material: Material) -> Option<&str> {
+        (0..self.len())
+            .map(|i| self.nth(i))
+            .find(|&(_, candidate)| candidate == material)
+            .map(|(name, _)| name)
+    }
+}
+
+impl Default for Materials {
+    fn default() -> Self {
+        Materials::new()
+    }
+}
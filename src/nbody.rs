@@ -0,0 +1,212 @@
+// Every ball pulls on every other one with Newtonian gravity, on top of
+// the world's gravity and fields. Summing every pair is O(n²), so past a
+// thousand balls they go into a Barnes–Hut quadtree first: a cell far
+// enough away pulls like a single ball of its whole mass at its center of
+// mass, only close cells are opened up.
+//
+// The tree is built from where the balls are at the start of a step, the
+// integrator's samples later in the step are pulled by those positions.
+// Only semi-implicit Euler, which samples once, keeps the total momentum
+// exactly, the others lose a little of it when balls pass close by.
+
+use std::ops::Range;
+
+use crate::ball::Ball;
+
+pub const GRAVITATIONAL_CONSTANT: f32 = 1.0; // in m³/(kg·s²)
+pub const SOFTENING: f32 = 0.1; // in m
+pub const THETA: f32 = 0.5;
+
+// up to this many balls every pair is summed, it's faster than the tree,
+// see benches/nbody.rs
+pub const DIRECT_LIMIT: usize = 1000;
+// cells stop splitting this deep, balls on the same spot share a leaf
+const MAX_DEPTH: u32 = 24;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gravitation {
+    pub constant: f32,  // G, in m³/(kg·s²)
+    pub softening: f32, // in m, balls closer than about this pull less than they would
+    // a cell pulls as one when its size over its distance is below this,
+    // 0 opens every cell
+    pub theta: f32,
+    pub merging: bool, // balls that touch become one
+}
+
+impl Default for Gravitation {
+    fn default() -> Self {
+        Gravitation {
+            constant: GRAVITATIONAL_CONSTANT,
+            softening: SOFTENING,
+            theta: THETA,
+            merging: false,
+        }
+    }
+}
+
+impl Gravitation {
+    // in m/s², what a mass in kg at from does to anything at pos
+    fn pull(&self, pos: raylib::Vector2, from: raylib::Vector2, mass: f32) -> raylib::Vector2 {
+        let offset = from - pos;
+        let squared = offset.length_squared() + self.softening * self.softening;
+        if squared == 0.0 {
+            return raylib::Vector2::zero();
+        }
+        offset * (self.constant * mass / (squared * squared.sqrt()))
+    }
+}
+
+#[derive(Clone)]
+struct Cell {
+    size: f32,               // in m, cells are square
+    mass: f32,               // in kg, of every ball inside
+    center: raylib::Vector2, // in m, the center of mass
+    children: Option<[usize; 4]>,
+    balls: Range<usize>, // into order, every ball inside
+}
+
+// The balls of one step and the tree over them, see Gravitation.
+pub struct Quadtree {
+    pub gravitation: Gravitation,
+    bodies: Vec<(raylib::Vector2, f32)>, // where every ball is and its mass
+    cells: Vec<Cell>,                    // the root first, empty up to DIRECT_LIMIT balls
+    order: Vec<usize>,                   // ball indices, every cell's are next to each other
+    slot: Vec<usize>,                    // where each ball is in order
+}
+
+impl Quadtree {
+    pub fn new(balls: &[Ball], gravitation: Gravitation) -> Self {
+        let bodies: Vec<(raylib::Vector2, f32)> = balls.iter().map(|ball| (ball.pos, ball.mass)).collect();
+        let mut tree = Quadtree {
+            gravitation,
+            order: (0..bodies.len()).collect(),
+            slot: Vec::new(),
+            cells: Vec::new(),
+            bodies,
+        };
+        if tree.bodies.len() > DIRECT_LIMIT {
+            let (mut min, mut max) = (tree.bodies[0].0, tree.bodies[0].0);
+            for &(pos, _) in tree.bodies.iter() {
+                min = raylib::Vector2::new(min.x.min(pos.x), min.y.min(pos.y));
+                max = raylib::Vector2::new(max.x.max(pos.x), max.y.max(pos.y));
+            }
+            // a little bigger so the balls on the far edges are inside too
+            let size = (max.x - min.x).max(max.y - min.y) * 1.001 + f32::EPSILON;
+            tree.build(min, size, 0..tree.bodies.len(), 0);
+        }
+        tree.slot = vec![0; tree.order.len()];
+        for (slot, &i) in tree.order.iter().enumerate() {
+            tree.slot[i] = slot;
+        }
+        tree
+    }
+
+    // adds the cell for the balls in order[balls] and everything below it,
+    // returns its index
+    fn build(&mut self, min: raylib::Vector2, size: f32, balls: Range<usize>, depth: u32) -> usize {
+        let (mut mass, mut weighted) = (0.0, raylib::Vector2::zero());
+        for &i in self.order[balls.clone()].iter() {
+            let (pos, ball_mass) = self.bodies[i];
+            mass += ball_mass;
+            weighted += pos * ball_mass;
+        }
+        let center = if mass > 0.0 { weighted / mass } else { min + raylib::Vector2::new_from(size / 2.0) };
+        let index = self.cells.len();
+        self.cells.push(Cell { size, mass, center, children: None, balls: balls.clone() });
+        if balls.len() <= 1 || depth == MAX_DEPTH {
+            return index;
+        }
+
+        // sorted by quadrant: top left, top right, bottom left, bottom right
+        let half = size / 2.0;
+        let middle = min + raylib::Vector2::new_from(half);
+        let bodies = &self.bodies;
+        let quadrant = |i: &usize| {
+            let pos = bodies[*i].0;
+            (pos.x >= middle.x) as usize + 2 * (pos.y >= middle.y) as usize
+        };
+        self.order[balls.clone()].sort_unstable_by_key(quadrant);
+        let mut ranges = [balls.start..balls.start, balls.clone(), balls.clone(), balls.clone()];
+        let mut start = balls.start;
+        for (q, range) in ranges.iter_mut().enumerate() {
+            let end = start + self.order[start..balls.end].iter().take_while(|i| quadrant(i) == q).count();
+            *range = start..end;
+            start = end;
+        }
+
+        let mut children = [0; 4];
+        for (q, (child, range)) in children.iter_mut().zip(ranges).enumerate() {
+            let corner = min + raylib::Vector2::new((q % 2) as f32 * half, (q / 2) as f32 * half);
+            *child = self.build(corner, half, range, depth + 1);
+        }
+        self.cells[index].children = Some(children);
+        index
+    }
+
+    // in m/s², what every ball but own does to anything at pos
+    pub fn acceleration(&self, pos: raylib::Vector2, own: usize) -> raylib::Vector2 {
+        let gravitation = &self.gravitation;
+        let mut total = raylib::Vector2::zero();
+        let pull_from = |i: usize| {
+            if i == own {
+                return raylib::Vector2::zero();
+            }
+            let (from, mass) = self.bodies[i];
+            gravitation.pull(pos, from, mass)
+        };
+        if self.cells.is_empty() {
+            return (0..self.bodies.len()).fold(total, |total, i| total + pull_from(i));
+        }
+
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let cell = &self.cells[index];
+            if cell.mass == 0.0 {
+                continue;
+            }
+            let Some(children) = cell.children else {
+                total = self.order[cell.balls.clone()].iter().fold(total, |total, &i| total + pull_from(i));
+                continue;
+            };
+            // a cell own is in is always opened, it doesn't pull on itself
+            let holds_own = cell.balls.contains(&self.slot[own]);
+            if !holds_own && cell.size < gravitation.theta * pos.distance(cell.center) {
+                total += gravitation.pull(pos, cell.center, cell.mass);
+            } else {
+                stack.extend(children);
+            }
+        }
+        total
+    }
+}
+
+// One ball of both their masses where their center of mass is, moving
+// with their momentum and spinning with their angular momentum. The
+// heavier one's material and look win, the area is kept.
+pub fn merge(a: &Ball, b: &Ball) -> Ball {
+    let heavy = if b.mass > a.mass { b } else { a };
+    let mass = a.mass + b.mass;
+    let center = (a.pos * a.mass + b.pos * b.mass) / mass;
+    let prev_center = (a.prev_pos * a.mass + b.prev_pos * b.mass) / mass;
+    let velocity = (a.velocity * a.mass + b.velocity * b.mass) / mass;
+
+    // spin of both plus their orbit around the shared center
+    let spin = |ball: &Ball| ball.inertia() * ball.angular_velocity;
+    let orbit = |ball: &Ball| ball.mass * (ball.pos - center).cross(ball.velocity - velocity);
+    let momentum = spin(a) + spin(b) + orbit(a) + orbit(b);
+
+    let mut merged = Ball {
+        pos: center,
+        prev_pos: prev_center,
+        velocity,
+        radius: (a.radius * a.radius + b.radius * b.radius).sqrt(),
+        mass,
+        bullet: a.bullet || b.bullet,
+        ..Ball::at(center, heavy.radius, heavy.material)
+    };
+    merged.color = heavy.color;
+    merged.angle = heavy.angle;
+    merged.prev_angle = heavy.prev_angle;
+    merged.angular_velocity = momentum / merged.inertia();
+    merged
+}
@@ -1,13 +1,46 @@
 use crate::ball::Ball;
+use crate::collider::Collider;
+use crate::contact::{rect_points, Manifold};
+use crate::field::Forces;
+use crate::integrator::Integrator;
+use crate::material::Material;
 
+// Where two bodies touch, the normal points from self to the other one.
+// Nothing gets pushed here, the contacts go to the Solver.
 pub trait Collision {
-    fn collision_with_ball(&mut self, ball: &mut Ball);
-    fn collision_with_rect<T>(rect: &mut T);
+    fn contact_with_ball(&self, ball: &Ball) -> Option<Manifold>;
+    fn contact_with_rect<T: RectBody>(&self, rect: &T) -> Option<Manifold>;
 }
 
 pub trait PhyObj {
-    unsafe fn update_gravity(&mut self, dt: f32);
-    unsafe fn update_movement(&mut self, dt: f32);
-    unsafe fn update_clamp(&mut self, screen: raylib::Vector2);
-    unsafe fn update(&mut self, screen: raylib::Vector2, dt: f32);
+    // sums the forces on the body, sets the velocity the solver starts from
+    // and the drift
+    fn integrate(&mut self, integrator: Integrator, forces: &Forces, dt: f32);
+    fn update_movement(&mut self, dt: f32);
+    // the normal points from the collider to self
+    fn contact_with_collider(&self, collider: &Collider) -> Option<Manifold>;
+    // moves by the velocity the solver left and the drift, stops at colliders
+    // in the way
+    fn update_position(&mut self, colliders: &[Collider], dt: f32);
+}
+
+// Anything rectangular the contact code can collide. Static bodies report
+// an inverse mass of 0, bodies that don't turn keep the defaults for the
+// angular part and the corners of rect.
+pub trait RectBody {
+    fn rect(&self) -> raylib::Rectangle; // in m, unrotated, it turns around the center
+    fn velocity(&self) -> raylib::Vector2;
+    fn inv_mass(&self) -> f32;
+    fn material(&self) -> Material;
+
+    fn angular_velocity(&self) -> f32 {
+        0.0
+    }
+    fn inv_inertia(&self) -> f32 {
+        0.0
+    }
+    // in m, clockwise
+    fn corners(&self) -> [raylib::Vector2; 4] {
+        rect_points(self.rect())
+    }
 }
@@ -1,33 +1,94 @@
-use crate::obj::{Collision, PhyObj};
-use crate::{Ball, GRAVITY_CONSTANT, PIXELS_PER_METER};
+use crate::ball::{Ball, PIXELS_PER_METER};
+use crate::ccd::{stop_at, sweep_polygon_polygon, SWEEP_THRESHOLD};
+use crate::collider::Collider;
+use crate::contact::{circle_convex, inverse, polygon_manifold, rect_points, Manifold};
+use crate::field::Forces;
+use crate::integrator::Integrator;
+use crate::material::{Combine, Friction, Material};
+use crate::obj::{Collision, PhyObj, RectBody};
+use crate::sleep::Sleep;
 use aid::{BoolTo, Ternary};
 use std::mem::zeroed;
 use std::time::Instant;
 
 pub const PLAYER_DEBUG_INFO: bool = false;
+
+// springy, but not as slippery as rubber so it can still run
+pub const PLAYER_MATERIAL: Material = Material {
+    restitution: 0.85,
+    friction: Friction::new(0.3, 0.2, 0.0),
+    density: 12.5,
+    restitution_combine: Combine::Average,
+    friction_combine: Combine::GeometricMean,
+};
+
+// What the player wants to do this step, filled in by whoever owns the
+// keyboard (or a replay, or a test).
+#[derive(Clone, Copy, Default)]
+pub struct PlayerInput {
+    pub left: bool,
+    pub right: bool,
+    pub jump: bool,
+    pub slam: bool,
+}
+
+// A box that turns around its center, off center hits make it spin.
+#[derive(Clone, Copy)]
 pub struct Player {
     pub pos: raylib::Vector2,      // in m
+    pub prev_pos: raylib::Vector2, // in m, pos at the start of the last step
     pub dim: raylib::Vector2,      // in m
     pub velocity: raylib::Vector2, // in m/s
-    pub elast: f32,                // in percent
+    pub drift: raylib::Vector2,    // in m, see Integrator::step
+    pub angle: f32,                // in rad, clockwise on screen
+    pub prev_angle: f32,           // in rad, angle at the start of the last step
+    pub angular_velocity: f32,     // in rad/s
     pub mass: f32,                 // in kg
+    pub material: Material,
     pub color: raylib::Color,
+    pub input: PlayerInput,
+    pub sleep: Sleep, // only for how long it's been resting, it never falls asleep
 }
 
 impl Player {
-    pub unsafe fn draw(&self) {
-        raylib::draw_rectangle_v(
-            self.pos.mult_value(PIXELS_PER_METER),
-            self.dim.mult_value(PIXELS_PER_METER),
-            self.color,
-        );
+    // solid box, in kg·m²
+    pub fn inertia(&self) -> f32 {
+        self.mass * (self.dim.x * self.dim.x + self.dim.y * self.dim.y) / 12.0
+    }
+
+    // in m, clockwise like rect_points, where the corners are with pos and angle
+    pub fn corners_at(&self, pos: raylib::Vector2, angle: f32) -> [raylib::Vector2; 4] {
+        let half = self.dim / 2.0;
+        let center = pos + half;
+        let unrotated = raylib::Rectangle { x: -half.x, y: -half.y, width: self.dim.x, height: self.dim.y };
+        rect_points(unrotated).map(|corner| center + corner.rotate(angle))
+    }
+
+    // whether moving by displacement in one step could skip past something
+    pub fn needs_sweep(&self, displacement: raylib::Vector2) -> bool {
+        displacement.length() > self.dim.x.min(self.dim.y) / 2.0 * SWEEP_THRESHOLD
+    }
+
+    // alpha blends between prev_pos and pos, see FixedTimestep::alpha
+    pub unsafe fn draw(&self, alpha: f32) {
+        let pos = self.prev_pos.lerp(self.pos, alpha);
+        let angle = self.prev_angle + (self.angle - self.prev_angle) * alpha;
+        let half = self.dim.mult_value(PIXELS_PER_METER / 2.0);
+        let center = pos.mult_value(PIXELS_PER_METER) + half;
+        let rec = raylib::Rectangle {
+            x: center.x,
+            y: center.y,
+            width: half.x * 2.0,
+            height: half.y * 2.0,
+        };
+        raylib::draw_rectangle_pro(rec, half, angle.to_degrees(), self.color);
 
         static mut TIME: Instant = unsafe { zeroed() };
         static mut VEL: f32 = 0.0;
 
         let tmp = TIME;
 
-        if tmp.elapsed().as_millis() >= 100 as u128 {
+        if tmp.elapsed().as_millis() >= 100 {
             VEL = self.velocity.pythagorean();
             TIME = Instant::now();
         }
@@ -35,141 +96,130 @@ impl Player {
         let tmp = VEL;
 
         let txt = format!("{:.1}", tmp);
-        let mut txt_pos = self.pos.mult_value(PIXELS_PER_METER);
+        let mut txt_pos = pos.mult_value(PIXELS_PER_METER);
         txt_pos.x += PIXELS_PER_METER - (raylib::measure_text(&txt, 20) as f32 / 2.0);
         raylib::draw_text(&txt, txt_pos.x as i32, txt_pos.y as i32, 20, raylib::MAROON);
     }
 
-    pub unsafe fn update_collision_with_balls(&mut self, balls: &mut Vec<Ball>) {
-        for ball in balls.iter_mut() {
-            self.collision_with_ball(ball);
-        }
-    }
-
-    pub unsafe fn new() -> Self {
+    pub fn new() -> Self {
         Player {
             pos: raylib::Vector2::new_from(5.0),
+            prev_pos: raylib::Vector2::new_from(5.0),
             velocity: raylib::Vector2::zero(),
+            drift: raylib::Vector2::zero(),
+            angle: 0.0,
+            prev_angle: 0.0,
+            angular_velocity: 0.0,
             dim: raylib::Vector2::new_from(2.0),
-            elast: 0.85,
             mass: 50.0,
+            material: PLAYER_MATERIAL,
             color: raylib::WHITE,
+            input: PlayerInput::default(),
+            sleep: Sleep::default(),
         }
     }
 }
 
-impl Collision for Player {
-    fn collision_with_ball(&mut self, ball: &mut Ball) {
-        unsafe {
-            if !raylib::check_collision_circle_rec(
-                ball.pos,
-                ball.radius,
-                raylib::Rectangle {
-                    x: self.pos.x,
-                    y: self.pos.y,
-                    width: self.dim.x,
-                    height: self.dim.y,
-                },
-            ) {
-                return;
-            }
-        }
+impl Default for Player {
+    fn default() -> Self {
+        Player::new()
+    }
+}
 
-        let closest = raylib::Vector2 {
-            x: ball.pos.x.clamp(self.pos.x, self.pos.x + self.dim.x),
-            y: ball.pos.y.clamp(self.pos.y, self.pos.y + self.dim.y),
-        };
+impl RectBody for Player {
+    fn rect(&self) -> raylib::Rectangle {
+        raylib::Rectangle {
+            x: self.pos.x,
+            y: self.pos.y,
+            width: self.dim.x,
+            height: self.dim.y,
+        }
+    }
 
-        let delta = ball.pos.sub(closest);
-        let dist = delta.pythagorean();
+    fn velocity(&self) -> raylib::Vector2 {
+        self.velocity
+    }
 
-        if dist == 0.0 || dist >= ball.radius {
-            return;
-        }
+    fn inv_mass(&self) -> f32 {
+        1.0 / self.mass
+    }
 
-        let normal = delta.div_value(dist);
-        let penetration = ball.radius - dist;
+    fn material(&self) -> Material {
+        self.material
+    }
 
-        // positional correction
-        let correction = normal.mult_value(penetration / 2.0);
-        ball.pos.addeq(correction);
-        self.pos.subeq(correction);
+    fn angular_velocity(&self) -> f32 {
+        self.angular_velocity
+    }
 
-        // relative velocity
-        let rv = ball.velocity.sub(self.velocity);
-        let vel_along_normal = rv.dot(normal);
-        if vel_along_normal > 0.0 {
-            return;
-        }
+    fn inv_inertia(&self) -> f32 {
+        inverse(self.inertia())
+    }
 
-        let elast = (self.elast + ball.elast) / 2.0;
-        let impulse_scalar =
-            -(1.0 + elast) * vel_along_normal / (1.0 / self.mass + 1.0 / ball.mass);
-        let impulse = normal.mult_value(impulse_scalar);
+    fn corners(&self) -> [raylib::Vector2; 4] {
+        self.corners_at(self.pos, self.angle)
+    }
+}
 
-        self.velocity.subeq(impulse.div_value(self.mass));
-        ball.velocity.addeq(impulse.div_value(ball.mass));
+impl Collision for Player {
+    fn contact_with_ball(&self, ball: &Ball) -> Option<Manifold> {
+        circle_convex(ball, &self.corners())
     }
 
-    fn collision_with_rect<T>(_rect: &mut T) {
-        todo!()
+    fn contact_with_rect<T: RectBody>(&self, rect: &T) -> Option<Manifold> {
+        polygon_manifold(&self.corners(), &rect.corners())
     }
 }
 
 impl PhyObj for Player {
-    unsafe fn update_gravity(&mut self, dt: f32) {
-        if raylib::float_equals(self.velocity.x, 0.0) != 0 {
-            self.velocity.x = 0.0;
-        }
-        if raylib::float_equals(self.velocity.y, 0.0) != 0 {
-            self.velocity.y = 0.0;
-        }
-
-        self.velocity.y += GRAVITY_CONSTANT * dt;
+    fn integrate(&mut self, integrator: Integrator, forces: &Forces, dt: f32) {
+        let mass = self.mass;
+        let center = self.pos + self.dim / 2.0;
+        (self.velocity, self.drift) = integrator.step(center, self.velocity, dt, |pos, _| forces.on(pos, mass) / mass);
     }
 
-    unsafe fn update_movement(&mut self, dt: f32) {
+    fn update_movement(&mut self, dt: f32) {
         const ACCELERATION: f32 = 15.0; // in m/s
         const MAX_SPEED: f32 = 35.0; // in m/s
         const JUMP_DIST: f32 = 10.0; // in m/s
 
-        self.velocity.x += (raylib::is_key_down(raylib::KeyboardKey::KeyD).f32()
-            - raylib::is_key_down(raylib::KeyboardKey::KeyA).f32())
-            * ACCELERATION
-            * dt;
+        self.velocity.x += (self.input.right.f32() - self.input.left.f32()) * ACCELERATION * dt;
 
         self.velocity.x = self.velocity.x.clamp(-MAX_SPEED, MAX_SPEED);
 
         // jump
-        self.velocity.y +=
-            raylib::is_key_pressed(raylib::KeyboardKey::KeyW).f32() * JUMP_DIST * -1.0;
+        self.velocity.y += -(self.input.jump.f32() * JUMP_DIST);
 
-        self.velocity.y = raylib::is_key_pressed(raylib::KeyboardKey::KeyS)
+        self.velocity.y = self
+            .input
+            .slam
             .ternary(self.velocity.y.abs() * 2.0, self.velocity.y);
     }
 
-    unsafe fn update_clamp(&mut self, screen: raylib::Vector2) {
-        let coords = screen.div_value(PIXELS_PER_METER);
-
-        if self.pos.y >= coords.y - self.dim.y || self.pos.y <= 0.0 {
-            self.pos.y = self.pos.y.clamp(0.0, coords.y - self.dim.y);
-            self.velocity.y *= -self.elast;
-        }
-        if self.pos.x <= 0.0 || self.pos.x >= coords.x - self.dim.x {
-            self.pos.x = self.pos.x.clamp(0.0, coords.x - self.dim.x);
-            self.velocity.x *= -self.elast;
-        }
+    fn contact_with_collider(&self, collider: &Collider) -> Option<Manifold> {
+        polygon_manifold(&collider.points, &self.corners())
     }
 
-    unsafe fn update(&mut self, screen: raylib::Vector2, dt: f32) {
-        self.update_gravity(dt);
-        self.update_movement(dt);
-        self.update_clamp(screen);
-
+    fn update_position(&mut self, colliders: &[Collider], dt: f32) {
         if PLAYER_DEBUG_INFO {
             println!("{}, {}", self.velocity.x, self.velocity.y);
         }
 
-        self.pos.addeq(self.velocity.mult_value(dt));
+        // stops at the first collider in the way
+        let displacement = self.velocity * dt + std::mem::take(&mut self.drift);
+        let hit = if self.needs_sweep(displacement) {
+            let corners = self.corners();
+            colliders
+                .iter()
+                .filter_map(|collider| sweep_polygon_polygon(&corners, displacement, &collider.points))
+                .min_by(f32::total_cmp)
+        } else {
+            None
+        };
+        self.pos = match hit {
+            Some(t) => stop_at(self.pos, displacement, t),
+            None => self.pos + displacement,
+        };
+        self.angle += self.angular_velocity * dt;
     }
 }
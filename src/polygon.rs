@@ -0,0 +1,170 @@
+use crate::ball::{Ball, PIXELS_PER_METER};
+use crate::collider::{convex, draw_convex, edges, Collider};
+use crate::contact::{circle_polygon, inverse, polygon_manifold, Manifold};
+use crate::field::Forces;
+use crate::integrator::Integrator;
+use crate::material::Material;
+use crate::obj::{Collision, PhyObj, RectBody};
+use crate::sleep::Sleep;
+
+// A convex body that turns, triangles, boxes or any other hull. points are
+// relative to the center of mass and unrotated, clockwise on screen.
+#[derive(Clone, Debug)]
+pub struct Polygon {
+    pub pos: raylib::Vector2,         // in m, the center of mass
+    pub prev_pos: raylib::Vector2,    // in m, pos at the start of the last step
+    pub velocity: raylib::Vector2,    // in m/s
+    pub drift: raylib::Vector2,       // in m, see Integrator::step
+    pub angle: f32,                   // in rad, clockwise on screen
+    pub prev_angle: f32,              // in rad, angle at the start of the last step
+    pub angular_velocity: f32,        // in rad/s
+    pub points: Vec<raylib::Vector2>, // in m
+    pub mass: f32,                    // in kg
+    pub material: Material,
+    pub color: raylib::Color,
+    pub sleep: Sleep,
+}
+
+impl Polygon {
+    // points in m where they are in the world, the mass comes from the
+    // material's density
+    pub fn new(points: Vec<raylib::Vector2>, material: Material) -> Result<Self, String> {
+        let points = convex(points)?;
+
+        // the centroid of the fan of triangles from the first point
+        let mut area = 0.0;
+        let mut center = raylib::Vector2::zero();
+        for i in 1..points.len() - 1 {
+            let (a, b, c) = (points[0], points[i], points[i + 1]);
+            let triangle = (b - a).cross(c - a) / 2.0;
+            area += triangle;
+            center += (a + b + c) * (triangle / 3.0);
+        }
+        let center = center / area;
+
+        let mut polygon = Polygon {
+            pos: center,
+            prev_pos: center,
+            velocity: raylib::Vector2::zero(),
+            drift: raylib::Vector2::zero(),
+            angle: 0.0,
+            prev_angle: 0.0,
+            angular_velocity: 0.0,
+            points: points.iter().map(|&point| point - center).collect(),
+            mass: 0.0,
+            material,
+            color: raylib::ORANGE,
+            sleep: Sleep::default(),
+        };
+        polygon.mass = polygon.mass_from_density();
+        Ok(polygon)
+    }
+
+    // angle in rad around the center, clockwise on screen
+    pub fn rect(center: raylib::Vector2, dim: raylib::Vector2, angle: f32, material: Material) -> Self {
+        let half = dim / 2.0;
+        let corners = vec![
+            raylib::Vector2::new(-half.x, -half.y),
+            raylib::Vector2::new(half.x, -half.y),
+            raylib::Vector2::new(half.x, half.y),
+            raylib::Vector2::new(-half.x, half.y),
+        ];
+        let mut polygon = Polygon::new(corners, material).expect("a box is convex");
+        polygon.pos = center;
+        polygon.prev_pos = center;
+        polygon.angle = angle;
+        polygon.prev_angle = angle;
+        polygon
+    }
+
+    // in m²
+    pub fn area(&self) -> f32 {
+        let points = &self.points;
+        (1..points.len() - 1)
+            .map(|i| (points[i] - points[0]).cross(points[i + 1] - points[0]) / 2.0)
+            .sum()
+    }
+
+    // in kg, what the material weighs over the area of the polygon
+    pub fn mass_from_density(&self) -> f32 {
+        self.material.density * self.area()
+    }
+
+    pub fn set_material(&mut self, material: Material) {
+        self.material = material;
+        self.mass = self.mass_from_density();
+    }
+
+    // solid plate of even density, in kg·m²
+    pub fn inertia(&self) -> f32 {
+        let mut area = 0.0;
+        let mut second_moment = 0.0;
+        for i in 0..self.points.len() {
+            let a = self.points[i];
+            let b = self.points[(i + 1) % self.points.len()];
+            let cross = a.cross(b);
+            area += cross / 2.0;
+            second_moment += cross * (a.dot(a) + a.dot(b) + b.dot(b)) / 12.0;
+        }
+        self.mass * second_moment / area
+    }
+
+    pub fn inv_inertia(&self) -> f32 {
+        inverse(self.inertia())
+    }
+
+    // furthest any point is from the center, in m
+    pub fn radius(&self) -> f32 {
+        self.points.iter().map(|point| point.length()).fold(0.0, f32::max)
+    }
+
+    // in m, where the points are in the world right now
+    pub fn vertices(&self) -> Vec<raylib::Vector2> {
+        self.points.iter().map(|&point| self.pos + point.rotate(self.angle)).collect()
+    }
+
+    pub fn contains(&self, point: raylib::Vector2) -> bool {
+        let vertices = self.vertices();
+        let inside = edges(&vertices).all(|(a, _, normal)| (point - a).dot(normal) <= 0.0);
+        inside
+    }
+
+    // alpha blends between prev_pos and pos, see FixedTimestep::alpha
+    pub unsafe fn draw(&self, alpha: f32) {
+        let pos = self.prev_pos.lerp(self.pos, alpha);
+        let angle = self.prev_angle + (self.angle - self.prev_angle) * alpha;
+        let vertices: Vec<raylib::Vector2> = self.points.iter().map(|&point| pos + point.rotate(angle)).collect();
+        draw_convex(&vertices, self.color);
+
+        // marker from the center to the first corner so spin is visible
+        raylib::draw_line_v(pos * PIXELS_PER_METER, vertices[0] * PIXELS_PER_METER, raylib::BLACK);
+    }
+}
+
+impl Collision for Polygon {
+    fn contact_with_ball(&self, ball: &Ball) -> Option<Manifold> {
+        circle_polygon(ball, self)
+    }
+
+    fn contact_with_rect<T: RectBody>(&self, rect: &T) -> Option<Manifold> {
+        polygon_manifold(&self.vertices(), &rect.corners())
+    }
+}
+
+impl PhyObj for Polygon {
+    fn integrate(&mut self, integrator: Integrator, forces: &Forces, dt: f32) {
+        let mass = self.mass;
+        (self.velocity, self.drift) = integrator.step(self.pos, self.velocity, dt, |pos, _| forces.on(pos, mass) / mass);
+    }
+
+    fn update_movement(&mut self, _dt: f32) {}
+
+    fn contact_with_collider(&self, collider: &Collider) -> Option<Manifold> {
+        polygon_manifold(&collider.points, &self.vertices())
+    }
+
+    fn update_position(&mut self, _colliders: &[Collider], dt: f32) {
+        self.pos += self.velocity * dt + std::mem::take(&mut self.drift);
+        self.angle += self.angular_velocity * dt;
+    }
+}
@@ -0,0 +1,243 @@
+// Recorded sessions: the world as it was when recording started plus the
+// input every step after that consumed. Since the simulation is
+// deterministic, feeding the same input into the same fixed steps gives the
+// same session again, glitches included.
+//
+// File layout, little endian:
+//
+//     b"PHYREPL\0", version: u32
+//     snapshot length: u64, snapshot (see snapshot.rs)
+//     input count: u64, one byte of PlayerInput bits per step
+
+use std::fs;
+use std::path::Path;
+
+use crate::player::PlayerInput;
+use crate::snapshot::Snapshot;
+use crate::world::World;
+
+pub const REPLAY_VERSION: u32 = 1;
+pub const REPLAY_EXTENSION: &str = "physrep";
+
+// seeking starts from the closest keyframe before the target and steps on
+// from there, one keyframe is kept every this many steps
+pub const KEYFRAME_INTERVAL: u64 = 600;
+pub const PLAYBACK_SPEEDS: [f32; 7] = [0.125, 0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+
+const MAGIC: &[u8; 8] = b"PHYREPL\0";
+const NORMAL_SPEED: usize = 3;
+
+#[derive(Clone)]
+pub struct Replay {
+    pub start: Snapshot, // holds the seed and everything the scene set up
+    pub inputs: Vec<PlayerInput>, // one per step
+}
+
+impl Replay {
+    pub fn seed(&self) -> u64 {
+        self.start.seed
+    }
+
+    pub fn len(&self) -> u64 {
+        self.inputs.len() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let snapshot = self.start.to_bytes();
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(REPLAY_VERSION.to_le_bytes());
+        bytes.extend((snapshot.len() as u64).to_le_bytes());
+        bytes.extend(snapshot);
+        bytes.extend(self.len().to_le_bytes());
+        bytes.extend(self.inputs.iter().map(|&input| input_to_bits(input)));
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut at: usize = 0;
+        let mut take = |len: usize| -> Result<&[u8], String> {
+            let end = at.checked_add(len).ok_or("replay is cut short")?;
+            let taken = bytes.get(at..end).ok_or("replay is cut short")?;
+            at += len;
+            Ok(taken)
+        };
+
+        if take(MAGIC.len())? != MAGIC {
+            return Err("not a replay".to_string());
+        }
+        let version = u32::from_le_bytes(take(4)?.try_into().unwrap());
+        if version != REPLAY_VERSION {
+            return Err(format!("replay version {version}, expected {REPLAY_VERSION}"));
+        }
+
+        let snapshot_len = u64::from_le_bytes(take(8)?.try_into().unwrap());
+        let start = Snapshot::from_bytes(take(snapshot_len as usize)?)?;
+
+        let input_len = u64::from_le_bytes(take(8)?.try_into().unwrap());
+        let inputs = take(input_len as usize)?
+            .iter()
+            .map(|&bits| input_from_bits(bits))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if at != bytes.len() {
+            return Err(format!("{} bytes left over", bytes.len() - at));
+        }
+        Ok(Replay { start, inputs })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+        }
+        fs::write(path, self.to_bytes()).map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|err| format!("{}: {err}", path.display()))?;
+        Replay::from_bytes(&bytes).map_err(|err| format!("{}: {err}", path.display()))
+    }
+}
+
+fn input_to_bits(input: PlayerInput) -> u8 {
+    input.left as u8 | (input.right as u8) << 1 | (input.jump as u8) << 2 | (input.slam as u8) << 3
+}
+
+fn input_from_bits(bits: u8) -> Result<PlayerInput, String> {
+    if bits >> 4 != 0 {
+        return Err(format!("invalid input {bits:#04x}"));
+    }
+    Ok(PlayerInput {
+        left: bits & 1 != 0,
+        right: bits & 2 != 0,
+        jump: bits & 4 != 0,
+        slam: bits & 8 != 0,
+    })
+}
+
+// Takes the place of World::advance while recording.
+pub struct Recorder {
+    pub replay: Replay,
+}
+
+impl Recorder {
+    pub fn start(world: &World) -> Self {
+        Recorder {
+            replay: Replay { start: Snapshot::capture(world), inputs: Vec::new() },
+        }
+    }
+
+    pub fn advance(&mut self, world: &mut World, frame_time: f32) -> f32 {
+        let dt = world.timestep.dt();
+        for _ in 0..world.timestep.advance(frame_time) {
+            self.replay.inputs.push(world.player.input);
+            world.step(dt);
+        }
+
+        world.timestep.alpha()
+    }
+}
+
+// Takes the place of World::advance while a replay plays. The world's own
+// input is overwritten every step, live input has no effect.
+pub struct Playback {
+    pub replay: Replay,
+    pub paused: bool,
+    speed: usize,              // index into PLAYBACK_SPEEDS
+    keyframes: Vec<Snapshot>, // keyframes[i] is at step i * KEYFRAME_INTERVAL
+}
+
+impl Playback {
+    pub fn start(replay: Replay, world: &mut World) -> Self {
+        replay.start.clone().restore(world);
+        world.timestep.reset();
+
+        Playback {
+            keyframes: vec![replay.start.clone()],
+            replay,
+            paused: false,
+            speed: NORMAL_SPEED,
+        }
+    }
+
+    // steps played so far
+    pub fn position(&self, world: &World) -> u64 {
+        world.tick - self.replay.start.tick
+    }
+
+    pub fn at_end(&self, world: &World) -> bool {
+        self.position(world) >= self.replay.len()
+    }
+
+    pub fn speed(&self) -> f32 {
+        PLAYBACK_SPEEDS[self.speed]
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed + 1).min(PLAYBACK_SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    // pauses itself at the end, returns the interpolation factor to draw with
+    pub fn advance(&mut self, world: &mut World, frame_time: f32) -> f32 {
+        if self.paused {
+            return world.timestep.alpha();
+        }
+
+        for _ in 0..world.timestep.advance(frame_time * self.speed()) {
+            if self.at_end(world) {
+                self.paused = true;
+                world.timestep.reset();
+                break;
+            }
+            self.step(world);
+        }
+
+        world.timestep.alpha()
+    }
+
+    // a single step, for going through a glitch frame by frame
+    pub fn step(&mut self, world: &mut World) {
+        let position = self.position(world);
+        if position >= self.replay.len() {
+            return;
+        }
+        if position == self.keyframes.len() as u64 * KEYFRAME_INTERVAL {
+            self.keyframes.push(Snapshot::capture(world));
+        }
+
+        world.player.input = self.replay.inputs[position as usize];
+        let dt = world.timestep.dt();
+        world.step(dt);
+    }
+
+    pub fn seek(&mut self, world: &mut World, position: u64) {
+        let position = position.min(self.replay.len());
+        let keyframe = ((position / KEYFRAME_INTERVAL) as usize).min(self.keyframes.len() - 1);
+
+        // no need to go back if the target is ahead and before the next keyframe
+        let current = self.position(world);
+        if current > position || current < keyframe as u64 * KEYFRAME_INTERVAL {
+            self.keyframes[keyframe].clone().restore(world);
+        }
+        while self.position(world) < position {
+            self.step(world);
+        }
+        world.timestep.reset();
+    }
+
+    // in s of simulated time, negative goes back
+    pub fn seek_by(&mut self, world: &mut World, seconds: f32) {
+        let steps = (seconds.abs() * world.timestep.hz).round() as u64;
+        let position = self.position(world);
+        let target = if seconds < 0.0 { position.saturating_sub(steps) } else { position + steps };
+        self.seek(world, target);
+    }
+}
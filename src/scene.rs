@@ -0,0 +1,1177 @@
+// Initial conditions of a World, loaded from a small subset of TOML:
+//
+//     # comments
+//     [world]                 one table
+//     [[ball]]                one more entry of a list of tables
+//     key = 1.5               numbers, "strings", true/false and [arrays]
+//
+// See assets/scenes/default.toml for every table and key. Errors carry the
+// line they were found on.
+
+use std::fmt;
+use std::fs;
+
+use crate::ball::{Ball, GRAVITY_CONSTANT, NUM_OF_BALLS, PIXELS_PER_METER};
+use crate::block::Block;
+use crate::collider::Collider;
+use crate::contact::Edges;
+use crate::field::{Falloff, Field, FieldKind};
+use crate::fluid::{Fluid, Pool};
+use crate::hoop::Hoop;
+use crate::integrator::Integrator;
+use crate::joint::{Bodies, Joint, JointKind, Rope};
+use crate::liquid::Liquid;
+use crate::material::{Combine, Friction, Material, Materials};
+use crate::nbody::Gravitation;
+use crate::player::Player;
+use crate::polygon::Polygon;
+use crate::sleep::Sleep;
+use crate::soft::{Lattice, LatticeKind, SoftBody, SOFT_SIZE};
+use crate::solver::SolverSettings;
+use crate::timestep::{MAX_STEPS_PER_FRAME, PHYSICS_HZ};
+
+// what fits into an 800x600 window
+pub const DEFAULT_BOUNDS: raylib::Vector2 = raylib::Vector2::new(800.0 / PIXELS_PER_METER, 600.0 / PIXELS_PER_METER);
+
+#[derive(Clone)]
+pub struct Scene {
+    pub bounds: raylib::Vector2,  // in m
+    pub gravity: raylib::Vector2, // in m/s²
+    pub fields: Vec<Field>,       // on top of gravity
+    pub air: Fluid,               // slows down balls outside the pools
+    pub pools: Vec<Pool>,
+    // balls pulling on each other, see nbody.rs
+    pub gravitation: Option<Gravitation>,
+    pub edges: Edges,
+    pub colliders: Vec<Collider>, // inside the walls the bounds get
+    pub materials: Materials, // the scene's own on top of the built in ones
+    pub solver: SolverSettings,
+    pub integrator: Integrator,
+    pub sleeping: bool, // whether resting bodies fall asleep
+    pub hz: f32,        // of the fixed timestep
+    pub max_steps: u32, // physics steps per frame at most, see FixedTimestep
+    pub seed: Option<u64>,
+    pub random_balls: usize, // spawned with the seed on top of `balls`
+    pub balls: Vec<Ball>,
+    pub player: Player,
+    pub hoops: Vec<Hoop>,
+    pub blocks: Vec<Block>,
+    pub polygons: Vec<Polygon>,
+    pub joints: Vec<Joint>, // ropes included
+    pub soft_bodies: Vec<SoftBody>, // their points and springs are in balls and joints
+    pub liquids: Vec<Liquid>,       // their particles are in balls
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Scene {
+            bounds: DEFAULT_BOUNDS,
+            gravity: raylib::Vector2::new(0.0, GRAVITY_CONSTANT),
+            fields: Vec::new(),
+            air: Fluid::default(),
+            pools: Vec::new(),
+            gravitation: None,
+            edges: Edges::default(),
+            colliders: Vec::new(),
+            materials: Materials::new(),
+            solver: SolverSettings::default(),
+            integrator: Integrator::default(),
+            sleeping: true,
+            hz: PHYSICS_HZ,
+            max_steps: MAX_STEPS_PER_FRAME,
+            seed: None,
+            random_balls: NUM_OF_BALLS,
+            balls: Vec::new(),
+            player: Player::new(),
+            hoops: vec![Hoop::new()],
+            blocks: default_blocks(),
+            polygons: Vec::new(),
+            joints: Vec::new(),
+            soft_bodies: Vec::new(),
+            liquids: Vec::new(),
+        }
+    }
+}
+
+// a ledge to stand on and a couple of crates to push around
+fn default_blocks() -> Vec<Block> {
+    vec![
+        Block::new_static(raylib::Vector2::new(8.0, 12.0), raylib::Vector2::new(6.0, 0.5)),
+        Block::new(raylib::Vector2::new(9.0, 9.0), raylib::Vector2::new(1.5, 1.5), 20.0),
+        Block::new(raylib::Vector2::new(15.0, 9.0), raylib::Vector2::new(1.0, 1.0), 10.0),
+    ]
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneError {
+    pub line: usize, // 1 based, 0 when there is no line to blame
+    pub message: String,
+}
+
+impl SceneError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        SceneError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            return write!(f, "{}", self.message);
+        }
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Scene {
+    pub fn load(path: &str) -> Result<Scene, String> {
+        let text = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+        Scene::parse(&text).map_err(|err| format!("{path}: {err}"))
+    }
+
+    pub fn parse(text: &str) -> Result<Scene, SceneError> {
+        let tables = parse_tables(text)?;
+
+        let mut scene = Scene {
+            random_balls: 0,
+            hoops: Vec::new(),
+            blocks: Vec::new(),
+            ..Scene::default()
+        };
+        let mut seen_world = false;
+        let mut seen_player = false;
+        let mut seen_random = false;
+        let mut seen_solver = false;
+        let mut seen_gravitation = false;
+        // made once every body is there, so they can hold on to any of them
+        let mut ropes: Vec<Rope> = Vec::new();
+        let mut lattices: Vec<Lattice> = Vec::new();
+        let mut liquids: Vec<(Liquid, raylib::Rectangle)> = Vec::new(); // and what they fill
+        let mut joints: Vec<(usize, Joint)> = Vec::new(); // with the line they're on
+
+        // materials first, so they can be used before they're defined
+        for table in tables.iter().filter(|table| table.name == "material" && table.is_list) {
+            let mut reader = Reader::new(table);
+            let name = reader.required("name", Reader::string)?;
+            if scene.materials.get(&name).is_some() {
+                return Err(SceneError::new(table.line_of("name"), format!("material `{name}` already exists")));
+            }
+            let base = reader.material("base", &scene.materials)?.unwrap_or(Material::RUBBER);
+            let material = Material {
+                restitution: reader.fraction("restitution")?.unwrap_or(base.restitution),
+                friction: reader.friction(base.friction)?,
+                density: reader.positive("density")?.unwrap_or(base.density),
+                restitution_combine: reader.named("restitution_combine")?.unwrap_or(base.restitution_combine),
+                friction_combine: reader.named("friction_combine")?.unwrap_or(base.friction_combine),
+            };
+            reader.finish()?;
+            scene.materials.custom.push((name, material));
+        }
+
+        for table in tables.iter() {
+            let mut reader = Reader::new(table);
+            match (table.name.as_str(), table.is_list) {
+                ("material", true) => continue,
+                ("world", false) => {
+                    once(&mut seen_world, table)?;
+                    scene.bounds = reader.size("bounds")?.unwrap_or(scene.bounds);
+                    scene.gravity = reader.vec2("gravity")?.unwrap_or(scene.gravity);
+                    scene.edges.floor = reader.material("floor_material", &scene.materials)?.unwrap_or(scene.edges.floor);
+                    scene.edges.walls = reader.material("wall_material", &scene.materials)?.unwrap_or(scene.edges.walls);
+                    scene.seed = reader.u64("seed")?;
+                    scene.sleeping = reader.bool("sleeping")?.unwrap_or(scene.sleeping);
+                    scene.hz = reader.positive("hz")?.unwrap_or(scene.hz);
+                    if let Some(max_steps) = reader.u64("max_steps")? {
+                        reader.check(max_steps >= 1, "max_steps", "has to be at least 1")?;
+                        scene.max_steps = max_steps as u32;
+                    }
+                    scene.integrator = reader.named("integrator")?.unwrap_or(scene.integrator);
+                    scene.air.density = reader.non_negative("air_density")?.unwrap_or(scene.air.density);
+                    scene.air.viscosity = reader.non_negative("air_viscosity")?.unwrap_or(scene.air.viscosity);
+                }
+                ("solver", false) => {
+                    once(&mut seen_solver, table)?;
+                    let solver = &mut scene.solver;
+                    if let Some(iterations) = reader.u64("iterations")? {
+                        reader.check(iterations >= 1, "iterations", "has to be at least 1")?;
+                        solver.velocity_iterations = iterations as u32;
+                    }
+                    // 0 leaves overlap alone
+                    if let Some(iterations) = reader.u64("position_iterations")? {
+                        solver.position_iterations = iterations as u32;
+                    }
+                    solver.warm_starting = reader.bool("warm_starting")?.unwrap_or(solver.warm_starting);
+                }
+                // being there turns it on
+                ("gravitation", false) => {
+                    once(&mut seen_gravitation, table)?;
+                    let base = Gravitation::default();
+                    scene.gravitation = Some(Gravitation {
+                        constant: reader.positive("constant")?.unwrap_or(base.constant),
+                        softening: reader.non_negative("softening")?.unwrap_or(base.softening),
+                        theta: reader.non_negative("theta")?.unwrap_or(base.theta),
+                        merging: reader.bool("merging")?.unwrap_or(base.merging),
+                    });
+                }
+                ("random_balls", false) => {
+                    once(&mut seen_random, table)?;
+                    scene.random_balls = reader.u64("count")?.unwrap_or(0) as usize;
+                }
+                ("player", false) => {
+                    once(&mut seen_player, table)?;
+                    let player = &mut scene.player;
+                    player.pos = reader.vec2("pos")?.unwrap_or(player.pos);
+                    player.prev_pos = player.pos;
+                    player.velocity = reader.vec2("velocity")?.unwrap_or(player.velocity);
+                    player.angular_velocity = reader.f32("angular_velocity")?.unwrap_or(player.angular_velocity);
+                    player.dim = reader.size("dim")?.unwrap_or(player.dim);
+                    player.mass = reader.positive("mass")?.unwrap_or(player.mass);
+                    player.material = reader.material("material", &scene.materials)?.unwrap_or(player.material);
+                    player.color = reader.color("color")?.unwrap_or(player.color);
+                }
+                ("ball", true) => {
+                    let pos = reader.required("pos", Reader::vec2)?;
+                    let mut ball = Ball {
+                        pos,
+                        prev_pos: pos,
+                        velocity: reader.vec2("velocity")?.unwrap_or_default(),
+                        drift: raylib::Vector2::zero(),
+                        angle: 0.0,
+                        prev_angle: 0.0,
+                        angular_velocity: reader.f32("angular_velocity")?.unwrap_or(0.0),
+                        radius: reader.positive("radius")?.unwrap_or(0.5),
+                        mass: 0.0,
+                        material: reader.material("material", &scene.materials)?.unwrap_or(Material::RUBBER),
+                        color: raylib::PINK,
+                        bullet: reader.bool("bullet")?.unwrap_or(false),
+                        sleep: Sleep::default(),
+                    };
+                    // a mass overrides the material's density
+                    ball.mass = reader.positive("mass")?.unwrap_or(ball.mass_from_density());
+                    ball.color = reader.color("color")?.unwrap_or(ball.color);
+                    scene.balls.push(ball);
+                }
+                ("hoop", true) => {
+                    let mut hoop = Hoop::new();
+                    match reader.vec2("pos")? {
+                        Some(pos) => {
+                            hoop.pos = pos;
+                            hoop.follow_edge = false;
+                        }
+                        None => hoop.follow_edge = true,
+                    }
+                    hoop.dim = reader.size("dim")?.unwrap_or(hoop.dim);
+                    hoop.color = reader.color("color")?.unwrap_or(hoop.color);
+                    hoop.material = reader.material("material", &scene.materials)?.unwrap_or(hoop.material);
+                    scene.hoops.push(hoop);
+                }
+                ("block", true) => {
+                    let pos = reader.required("pos", Reader::vec2)?;
+                    let dim = reader.required("dim", Reader::size)?;
+                    // no mass means it never moves
+                    let mut block = match reader.positive("mass")? {
+                        Some(mass) => Block::new(pos, dim, mass),
+                        None => Block::new_static(pos, dim),
+                    };
+                    block.velocity = reader.vec2("velocity")?.unwrap_or(block.velocity);
+                    block.material = reader.material("material", &scene.materials)?.unwrap_or(block.material);
+                    block.color = reader.color("color")?.unwrap_or(block.color);
+                    scene.blocks.push(block);
+                }
+                ("segment", true) => {
+                    let from = reader.required("from", Reader::vec2)?;
+                    let to = reader.required("to", Reader::vec2)?;
+                    reader.check(from != to, "to", "has to be somewhere else than `from`")?;
+                    scene.colliders.push(reader.collider(Collider::segment(from, to), &scene.materials)?);
+                }
+                ("box", true) => {
+                    let pos = reader.required("pos", Reader::vec2)?;
+                    let dim = reader.required("dim", Reader::size)?;
+                    let angle = reader.f32("angle")?.unwrap_or(0.0).to_radians();
+                    if reader.bool("dynamic")?.unwrap_or(false) {
+                        let polygon = Polygon::rect(pos, dim, angle, Material::WOOD);
+                        scene.polygons.push(reader.polygon(polygon, &scene.materials)?);
+                    } else {
+                        scene.colliders.push(reader.collider(Collider::rect(pos, dim, angle), &scene.materials)?);
+                    }
+                }
+                ("polygon", true) => {
+                    let points = reader.required("points", Reader::points)?;
+                    let invalid = |err| SceneError::new(table.line_of("points"), err);
+                    if reader.bool("dynamic")?.unwrap_or(false) {
+                        let polygon = Polygon::new(points, Material::WOOD).map_err(invalid)?;
+                        scene.polygons.push(reader.polygon(polygon, &scene.materials)?);
+                    } else {
+                        let polygon = Collider::polygon(points).map_err(invalid)?;
+                        scene.colliders.push(reader.collider(polygon, &scene.materials)?);
+                    }
+                }
+                ("joint", true) => {
+                    let kind = reader.required("kind", Reader::named::<JointKind>)?;
+                    let (from, to) = match kind {
+                        JointKind::Pin => {
+                            let at = reader.required("at", Reader::vec2)?;
+                            (at, at)
+                        }
+                        _ => (reader.required("from", Reader::vec2)?, reader.required("to", Reader::vec2)?),
+                    };
+                    let mut joint = Joint::between(kind, from, to);
+                    joint.length = reader.non_negative("length")?.unwrap_or(from.distance(to));
+                    if kind == JointKind::Spring {
+                        joint.stiffness = reader.non_negative("stiffness")?.unwrap_or(joint.stiffness);
+                        joint.damping = reader.non_negative("damping")?.unwrap_or(joint.damping);
+                        reader.check(joint.stiffness + joint.damping > 0.0, "stiffness", "and `damping` can't both be 0")?;
+                    }
+                    let line = table.line_of(if kind == JointKind::Pin { "at" } else { "from" });
+                    joints.push((line, joint));
+                }
+                ("rope", true) => {
+                    let from = reader.required("from", Reader::vec2)?;
+                    let to = reader.required("to", Reader::vec2)?;
+                    reader.check(from != to, "to", "has to be somewhere else than `from`")?;
+                    let mut rope = Rope::new(from, to);
+                    if let Some(links) = reader.u64("links")? {
+                        reader.check(links >= 2, "links", "has to be at least 2")?;
+                        rope.links = links as usize;
+                    }
+                    rope.radius = reader.positive("radius")?.unwrap_or(rope.radius);
+                    rope.material = reader.material("material", &scene.materials)?.unwrap_or(rope.material);
+                    rope.color = reader.color("color")?.unwrap_or(rope.color);
+                    rope.pin_from = reader.bool("pin_from")?.unwrap_or(rope.pin_from);
+                    rope.pin_to = reader.bool("pin_to")?.unwrap_or(rope.pin_to);
+                    ropes.push(rope);
+                }
+                ("field", true) => {
+                    let kind = reader.required("kind", Reader::named::<FieldKind>)?;
+                    let mut field = match kind {
+                        FieldKind::Uniform => Field::uniform(reader.required("acceleration", Reader::vec2)?),
+                        FieldKind::Point | FieldKind::Vortex => {
+                            let center = reader.required("center", Reader::vec2)?;
+                            let mut field = Field::around(kind, center, reader.required("strength", Reader::f32)?);
+                            field.falloff = reader.named("falloff")?.unwrap_or(field.falloff);
+                            field.radius = reader.positive("radius")?.unwrap_or(field.radius);
+                            field
+                        }
+                    };
+                    // a box like a block's, the field only acts inside it
+                    match (reader.vec2("region_pos")?, reader.size("region_dim")?) {
+                        (Some(pos), Some(dim)) => {
+                            field.region = Some(raylib::Rectangle { x: pos.x, y: pos.y, width: dim.x, height: dim.y });
+                        }
+                        (None, None) => {}
+                        (Some(_), None) => return Err(SceneError::new(table.line_of("region_pos"), "`region_pos` needs a `region_dim`")),
+                        (None, Some(_)) => return Err(SceneError::new(table.line_of("region_dim"), "`region_dim` needs a `region_pos`")),
+                    }
+                    scene.fields.push(field);
+                }
+                ("soft_body", true) => {
+                    let kind = reader.required("kind", Reader::named::<LatticeKind>)?;
+                    let center = reader.required("center", Reader::vec2)?;
+                    let size = reader.size("size")?.unwrap_or(raylib::Vector2::new_from(SOFT_SIZE));
+                    let mut lattice = match kind {
+                        LatticeKind::Ring => Lattice { size, ..Lattice::ring(center, SOFT_SIZE) },
+                        LatticeKind::Grid => Lattice::grid(center, size),
+                    };
+                    if let Some(points) = reader.u64("points")? {
+                        let least = if kind == LatticeKind::Ring { 3 } else { 2 };
+                        reader.check(points >= least, "points", &format!("has to be at least {least}"))?;
+                        lattice.points = points as usize;
+                    }
+                    lattice.radius = reader.positive("radius")?.unwrap_or(lattice.radius);
+                    lattice.material = reader.material("material", &scene.materials)?.unwrap_or(lattice.material);
+                    lattice.color = reader.color("color")?.unwrap_or(lattice.color);
+                    lattice.stiffness = reader.positive("stiffness")?.unwrap_or(lattice.stiffness);
+                    lattice.damping = reader.non_negative("damping")?.unwrap_or(lattice.damping);
+                    lattice.pressure = reader.non_negative("pressure")?.unwrap_or(lattice.pressure);
+                    lattice.shape_stiffness = reader.non_negative("shape_stiffness")?.unwrap_or(lattice.shape_stiffness);
+                    lattices.push(lattice);
+                }
+                ("pool", true) => {
+                    let pos = reader.required("pos", Reader::vec2)?;
+                    let dim = reader.required("dim", Reader::size)?;
+                    let mut pool = Pool::new(raylib::Rectangle { x: pos.x, y: pos.y, width: dim.x, height: dim.y });
+                    pool.fluid.density = reader.non_negative("density")?.unwrap_or(pool.fluid.density);
+                    pool.fluid.viscosity = reader.non_negative("viscosity")?.unwrap_or(pool.fluid.viscosity);
+                    pool.color = reader.color("color")?.unwrap_or(pool.color);
+                    scene.pools.push(pool);
+                }
+                ("liquid", true) => {
+                    let pos = reader.required("pos", Reader::vec2)?;
+                    let dim = reader.required("dim", Reader::size)?;
+                    let mut liquid = Liquid::new();
+                    liquid.spacing = reader.positive("spacing")?.unwrap_or(liquid.spacing);
+                    liquid.density = reader.positive("density")?.unwrap_or(liquid.density);
+                    if let Some(viscosity) = reader.non_negative("viscosity")? {
+                        reader.check(viscosity <= 1.0, "viscosity", "can't be more than 1")?;
+                        liquid.viscosity = viscosity;
+                    }
+                    liquid.color = reader.color("color")?.unwrap_or(liquid.color);
+                    liquids.push((liquid, raylib::Rectangle { x: pos.x, y: pos.y, width: dim.x, height: dim.y }));
+                }
+                (name, is_list) => {
+                    let known = [
+                        "world",
+                        "solver",
+                        "gravitation",
+                        "random_balls",
+                        "material",
+                        "player",
+                        "ball",
+                        "hoop",
+                        "block",
+                        "segment",
+                        "box",
+                        "polygon",
+                        "joint",
+                        "rope",
+                        "field",
+                        "pool",
+                        "soft_body",
+                        "liquid",
+                    ];
+                    let message = if !known.contains(&name) {
+                        format!("unknown table `{name}`, expected one of {}", known.join(", "))
+                    } else if is_list {
+                        format!("`{name}` is a single table, use [{name}]")
+                    } else {
+                        format!("`{name}` is a list, use [[{name}]]")
+                    };
+                    return Err(SceneError::new(table.line, message));
+                }
+            }
+            reader.finish()?;
+        }
+
+        let bounds = scene.bounds;
+        for (table, pos) in tables
+            .iter()
+            .filter(|table| table.name == "ball")
+            .zip(scene.balls.iter().map(|ball| ball.pos))
+        {
+            if pos.x < 0.0 || pos.y < 0.0 || pos.x > bounds.x || pos.y > bounds.y {
+                return Err(SceneError::new(
+                    table.line_of("pos"),
+                    "ball is outside of the world bounds",
+                ));
+            }
+        }
+
+        for lattice in lattices {
+            let (balls, springs, soft) = lattice.build(scene.balls.len());
+            scene.balls.extend(balls);
+            scene.joints.extend(springs);
+            scene.soft_bodies.push(soft);
+        }
+        for (mut liquid, rect) in liquids {
+            let particles = liquid.pour(rect, scene.balls.len());
+            scene.balls.extend(particles);
+            scene.liquids.push(liquid);
+        }
+        for rope in ropes {
+            let bodies = scene.bodies();
+            let (balls, rope_joints) = rope.build(&bodies, scene.balls.len());
+            scene.balls.extend(balls);
+            scene.joints.extend(rope_joints);
+        }
+        for (line, joint) in joints {
+            let Some(attached) = scene.bodies().attach(joint) else {
+                let message = match joint.kind {
+                    JointKind::Pin => "there's nothing to pin there",
+                    _ => "both ends are on the same body, or on nothing that moves",
+                };
+                return Err(SceneError::new(line, message));
+            };
+            scene.joints.push(attached);
+        }
+
+        Ok(scene)
+    }
+
+    // what joints hold on to, see joint.rs
+    fn bodies(&self) -> Bodies<'_> {
+        Bodies { balls: &self.balls, blocks: &self.blocks, polygons: &self.polygons, player: &self.player }
+    }
+}
+
+fn once(seen: &mut bool, table: &Table) -> Result<(), SceneError> {
+    if *seen {
+        return Err(SceneError::new(table.line, format!("{} is defined twice", table.header())));
+    }
+    *seen = true;
+    Ok(())
+}
+
+pub fn color_by_name(name: &str) -> Option<raylib::Color> {
+    let color = match name {
+        "lightgray" => raylib::LIGHTGRAY,
+        "gray" => raylib::GRAY,
+        "darkgray" => raylib::DARKGRAY,
+        "yellow" => raylib::YELLOW,
+        "gold" => raylib::GOLD,
+        "orange" => raylib::ORANGE,
+        "pink" => raylib::PINK,
+        "red" => raylib::RED,
+        "maroon" => raylib::MAROON,
+        "green" => raylib::GREEN,
+        "lime" => raylib::LIME,
+        "darkgreen" => raylib::DARKGREEN,
+        "skyblue" => raylib::SKYBLUE,
+        "blue" => raylib::BLUE,
+        "darkblue" => raylib::DARKBLUE,
+        "purple" => raylib::PURPLE,
+        "violet" => raylib::VIOLET,
+        "darkpurple" => raylib::DARKPURPLE,
+        "beige" => raylib::BEIGE,
+        "brown" => raylib::BROWN,
+        "darkbrown" => raylib::DARKBROWN,
+        "white" => raylib::WHITE,
+        "black" => raylib::BLACK,
+        "magenta" => raylib::MAGENTA,
+        "raywhite" => raylib::RAYWHITE,
+        _ => return None,
+    };
+    Some(color)
+}
+
+// Parsing
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "a number",
+            Value::Str(_) => "a string",
+            Value::Bool(_) => "a boolean",
+            Value::Array(_) => "an array",
+        }
+    }
+}
+
+struct Entry {
+    key: String,
+    value: Value,
+    line: usize,
+}
+
+struct Table {
+    name: String,
+    is_list: bool,
+    line: usize,
+    entries: Vec<Entry>,
+}
+
+impl Table {
+    fn header(&self) -> String {
+        match self.is_list {
+            true => format!("[[{}]]", self.name),
+            false => format!("[{}]", self.name),
+        }
+    }
+
+    fn line_of(&self, key: &str) -> usize {
+        self.entries
+            .iter()
+            .find(|entry| entry.key == key)
+            .map_or(self.line, |entry| entry.line)
+    }
+}
+
+fn parse_tables(text: &str) -> Result<Vec<Table>, SceneError> {
+    let mut tables: Vec<Table> = Vec::new();
+
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let content = strip_comment(raw).trim();
+        if content.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = content.strip_prefix('[') {
+            let (name, is_list) = match header.strip_prefix('[') {
+                Some(rest) => (rest.strip_suffix("]]"), true),
+                None => (header.strip_suffix(']'), false),
+            };
+            let name = name
+                .map(str::trim)
+                .filter(|name| is_bare_key(name))
+                .ok_or_else(|| SceneError::new(line, format!("malformed table header `{content}`")))?;
+
+            tables.push(Table {
+                name: name.to_string(),
+                is_list,
+                line,
+                entries: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some((key, value)) = content.split_once('=') else {
+            return Err(SceneError::new(line, format!("expected `key = value`, found `{content}`")));
+        };
+        let key = key.trim();
+        if !is_bare_key(key) {
+            return Err(SceneError::new(line, format!("invalid key `{key}`")));
+        }
+
+        let Some(table) = tables.last_mut() else {
+            return Err(SceneError::new(line, format!("`{key}` has to be inside a [table]")));
+        };
+        if table.entries.iter().any(|entry| entry.key == key) {
+            return Err(SceneError::new(line, format!("`{key}` is set twice")));
+        }
+
+        let mut parser = ValueParser {
+            chars: value.trim().chars().collect(),
+            at: 0,
+            line,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.at != parser.chars.len() {
+            return Err(SceneError::new(line, "unexpected characters after the value"));
+        }
+
+        table.entries.push(Entry {
+            key: key.to_string(),
+            value,
+            line,
+        });
+    }
+
+    Ok(tables)
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+// everything up to a # that isn't inside a string
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut chars = line.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+struct ValueParser {
+    chars: Vec<char>,
+    at: usize,
+    line: usize,
+}
+
+impl ValueParser {
+    fn error(&self, message: impl Into<String>) -> SceneError {
+        SceneError::new(self.line, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.at).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.at += 1;
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, SceneError> {
+        self.skip_whitespace();
+        match self.peek() {
+            None => Err(self.error("missing value")),
+            Some('"') => self.string(),
+            Some('[') => self.array(),
+            Some(_) => self.word(),
+        }
+    }
+
+    fn string(&mut self) -> Result<Value, SceneError> {
+        self.at += 1;
+        let mut string = String::new();
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.at += 1;
+            match c {
+                '"' => return Ok(Value::Str(string)),
+                '\\' => {
+                    let escaped = match self.peek() {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        _ => return Err(self.error("unknown escape in string")),
+                    };
+                    self.at += 1;
+                    string.push(escaped);
+                }
+                c => string.push(c),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, SceneError> {
+        self.at += 1;
+        let mut values = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(']') {
+                self.at += 1;
+                return Ok(Value::Array(values));
+            }
+
+            values.push(self.value()?);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.at += 1,
+                Some(']') => {}
+                Some(c) => return Err(self.error(format!("expected `,` or `]` in array, found `{c}`"))),
+                None => return Err(self.error("unterminated array, arrays have to fit on one line")),
+            }
+        }
+    }
+
+    // numbers and booleans
+    fn word(&mut self) -> Result<Value, SceneError> {
+        let start = self.at;
+        while self
+            .peek()
+            .is_some_and(|c| !c.is_whitespace() && c != ',' && c != ']')
+        {
+            self.at += 1;
+        }
+        let word: String = self.chars[start..self.at].iter().collect();
+
+        match word.as_str() {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            _ => word
+                .replace('_', "")
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .map(Value::Number)
+                .ok_or_else(|| self.error(format!("`{word}` is not a number, string, boolean or array"))),
+        }
+    }
+}
+
+// Enums a scene names with a string, every value and what it's called
+trait Named: Copy + 'static {
+    const ALL: &'static [Self];
+    const WHAT: &'static str; // for errors
+    fn name(self) -> &'static str;
+}
+
+impl Named for Combine {
+    const ALL: &'static [Self] = &Combine::ALL;
+    const WHAT: &'static str = "rule";
+    fn name(self) -> &'static str {
+        Combine::name(self)
+    }
+}
+
+impl Named for Integrator {
+    const ALL: &'static [Self] = &Integrator::ALL;
+    const WHAT: &'static str = "integrator";
+    fn name(self) -> &'static str {
+        Integrator::name(self)
+    }
+}
+
+impl Named for JointKind {
+    const ALL: &'static [Self] = &JointKind::ALL;
+    const WHAT: &'static str = "joint";
+    fn name(self) -> &'static str {
+        JointKind::name(self)
+    }
+}
+
+impl Named for FieldKind {
+    const ALL: &'static [Self] = &FieldKind::ALL;
+    const WHAT: &'static str = "field";
+    fn name(self) -> &'static str {
+        FieldKind::name(self)
+    }
+}
+
+impl Named for Falloff {
+    const ALL: &'static [Self] = &Falloff::ALL;
+    const WHAT: &'static str = "falloff";
+    fn name(self) -> &'static str {
+        Falloff::name(self)
+    }
+}
+
+impl Named for LatticeKind {
+    const ALL: &'static [Self] = &LatticeKind::ALL;
+    const WHAT: &'static str = "soft body";
+    fn name(self) -> &'static str {
+        LatticeKind::name(self)
+    }
+}
+
+// Typed access to a table, remembers which keys were read so leftovers can
+// be reported as typos.
+struct Reader<'a> {
+    table: &'a Table,
+    used: Vec<bool>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(table: &'a Table) -> Self {
+        Reader {
+            table,
+            used: vec![false; table.entries.len()],
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<&'a Entry> {
+        let index = self.table.entries.iter().position(|entry| entry.key == key)?;
+        self.used[index] = true;
+        Some(&self.table.entries[index])
+    }
+
+    fn wrong_type(&self, entry: &Entry, expected: &str) -> SceneError {
+        SceneError::new(
+            entry.line,
+            format!("`{}` has to be {expected}, found {}", entry.key, entry.value.kind()),
+        )
+    }
+
+    fn check(&self, ok: bool, key: &str, message: &str) -> Result<(), SceneError> {
+        if ok {
+            return Ok(());
+        }
+        Err(SceneError::new(self.table.line_of(key), format!("`{key}` {message}")))
+    }
+
+    fn required<T>(
+        &mut self,
+        key: &str,
+        read: fn(&mut Self, &str) -> Result<Option<T>, SceneError>,
+    ) -> Result<T, SceneError> {
+        let table = self.table;
+        read(self, key)?.ok_or_else(|| {
+            SceneError::new(table.line, format!("{} is missing `{key}`", table.header()))
+        })
+    }
+
+    fn f32(&mut self, key: &str) -> Result<Option<f32>, SceneError> {
+        let Some(entry) = self.get(key) else {
+            return Ok(None);
+        };
+        match entry.value {
+            Value::Number(number) => Ok(Some(number as f32)),
+            _ => Err(self.wrong_type(entry, "a number")),
+        }
+    }
+
+    fn positive(&mut self, key: &str) -> Result<Option<f32>, SceneError> {
+        let value = self.f32(key)?;
+        self.check(value.is_none_or(|value| value > 0.0), key, "has to be positive")?;
+        Ok(value)
+    }
+
+    fn fraction(&mut self, key: &str) -> Result<Option<f32>, SceneError> {
+        let value = self.f32(key)?;
+        self.check(
+            value.is_none_or(|value| (0.0..=1.0).contains(&value)),
+            key,
+            "has to be between 0 and 1",
+        )?;
+        Ok(value)
+    }
+
+    fn non_negative(&mut self, key: &str) -> Result<Option<f32>, SceneError> {
+        let value = self.f32(key)?;
+        self.check(value.is_none_or(|value| value >= 0.0), key, "can't be negative")?;
+        Ok(value)
+    }
+
+    // `static_friction`, `friction` (dynamic) and `rolling_friction`
+    fn friction(&mut self, default: Friction) -> Result<Friction, SceneError> {
+        Ok(Friction {
+            static_coef: self.non_negative("static_friction")?.unwrap_or(default.static_coef),
+            dynamic_coef: self.non_negative("friction")?.unwrap_or(default.dynamic_coef),
+            rolling: self.non_negative("rolling_friction")?.unwrap_or(default.rolling),
+        })
+    }
+
+    fn string(&mut self, key: &str) -> Result<Option<String>, SceneError> {
+        let Some(entry) = self.get(key) else {
+            return Ok(None);
+        };
+        match &entry.value {
+            Value::Str(value) => Ok(Some(value.clone())),
+            _ => Err(self.wrong_type(entry, "a string")),
+        }
+    }
+
+    // by name, built in or from a [[material]] table
+    fn material(&mut self, key: &str, materials: &Materials) -> Result<Option<Material>, SceneError> {
+        let Some(name) = self.string(key)? else {
+            return Ok(None);
+        };
+        match materials.get(&name.to_lowercase()) {
+            Some(material) => Ok(Some(material)),
+            None => Err(SceneError::new(self.table.line_of(key), format!("unknown material `{name}`"))),
+        }
+    }
+
+    // one of T::ALL, by name
+    fn named<T: Named>(&mut self, key: &str) -> Result<Option<T>, SceneError> {
+        let Some(name) = self.string(key)? else {
+            return Ok(None);
+        };
+        match T::ALL.iter().copied().find(|value| value.name() == name.to_lowercase()) {
+            Some(value) => Ok(Some(value)),
+            None => {
+                let names: Vec<&str> = T::ALL.iter().map(|value| value.name()).collect();
+                let message = format!("unknown {} `{name}`, expected one of {}", T::WHAT, names.join(", "));
+                Err(SceneError::new(self.table.line_of(key), message))
+            }
+        }
+    }
+
+    fn u64(&mut self, key: &str) -> Result<Option<u64>, SceneError> {
+        let Some(entry) = self.get(key) else {
+            return Ok(None);
+        };
+        match entry.value {
+            Value::Number(number) if number >= 0.0 && number.fract() == 0.0 => Ok(Some(number as u64)),
+            _ => Err(self.wrong_type(entry, "a whole non-negative number")),
+        }
+    }
+
+    fn bool(&mut self, key: &str) -> Result<Option<bool>, SceneError> {
+        let Some(entry) = self.get(key) else {
+            return Ok(None);
+        };
+        match entry.value {
+            Value::Bool(value) => Ok(Some(value)),
+            _ => Err(self.wrong_type(entry, "true or false")),
+        }
+    }
+
+    fn vec2(&mut self, key: &str) -> Result<Option<raylib::Vector2>, SceneError> {
+        let Some(entry) = self.get(key) else {
+            return Ok(None);
+        };
+        match &entry.value {
+            Value::Array(values) => match values.as_slice() {
+                [Value::Number(x), Value::Number(y)] => Ok(Some(raylib::Vector2::new(*x as f32, *y as f32))),
+                _ => Err(self.wrong_type(entry, "[x, y]")),
+            },
+            _ => Err(self.wrong_type(entry, "[x, y]")),
+        }
+    }
+
+    // [[x, y], [x, y], ...]
+    fn points(&mut self, key: &str) -> Result<Option<Vec<raylib::Vector2>>, SceneError> {
+        let Some(entry) = self.get(key) else {
+            return Ok(None);
+        };
+        let Value::Array(values) = &entry.value else {
+            return Err(self.wrong_type(entry, "[[x, y], ...]"));
+        };
+        let points = values
+            .iter()
+            .map(|value| match value {
+                Value::Array(xy) => match xy.as_slice() {
+                    [Value::Number(x), Value::Number(y)] => Some(raylib::Vector2::new(*x as f32, *y as f32)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        points.map(Some).ok_or_else(|| self.wrong_type(entry, "[[x, y], ...]"))
+    }
+
+    // what every collider table has on top of its shape
+    fn collider(&mut self, collider: Collider, materials: &Materials) -> Result<Collider, SceneError> {
+        Ok(Collider {
+            material: self.material("material", materials)?.unwrap_or(collider.material),
+            color: self.color("color")?.unwrap_or(collider.color),
+            ..collider
+        })
+    }
+
+    // what dynamic [[box]] and [[polygon]] tables have on top of their shape
+    fn polygon(&mut self, mut polygon: Polygon, materials: &Materials) -> Result<Polygon, SceneError> {
+        polygon.velocity = self.vec2("velocity")?.unwrap_or(polygon.velocity);
+        polygon.angular_velocity = self.f32("angular_velocity")?.unwrap_or(polygon.angular_velocity);
+        polygon.material = self.material("material", materials)?.unwrap_or(polygon.material);
+        // a mass overrides the material's density
+        polygon.mass = self.positive("mass")?.unwrap_or(polygon.mass_from_density());
+        polygon.color = self.color("color")?.unwrap_or(polygon.color);
+        Ok(polygon)
+    }
+
+    fn size(&mut self, key: &str) -> Result<Option<raylib::Vector2>, SceneError> {
+        let value = self.vec2(key)?;
+        self.check(
+            value.is_none_or(|value| value.x > 0.0 && value.y > 0.0),
+            key,
+            "has to be positive",
+        )?;
+        Ok(value)
+    }
+
+    // "name" or [r, g, b] or [r, g, b, a] with 0-255 components
+    fn color(&mut self, key: &str) -> Result<Option<raylib::Color>, SceneError> {
+        let Some(entry) = self.get(key) else {
+            return Ok(None);
+        };
+        let expected = "a color name or [r, g, b, a]";
+        match &entry.value {
+            Value::Str(name) => color_by_name(&name.to_lowercase())
+                .map(Some)
+                .ok_or_else(|| SceneError::new(entry.line, format!("unknown color `{name}`"))),
+            Value::Array(values) if values.len() == 3 || values.len() == 4 => {
+                let mut rgba = [255u8; 4];
+                for (i, value) in values.iter().enumerate() {
+                    match value {
+                        Value::Number(number) if (0.0..=255.0).contains(number) => rgba[i] = *number as u8,
+                        _ => return Err(self.wrong_type(entry, expected)),
+                    }
+                }
+                Ok(Some(raylib::Color {
+                    r: rgba[0],
+                    g: rgba[1],
+                    b: rgba[2],
+                    a: rgba[3],
+                }))
+            }
+            _ => Err(self.wrong_type(entry, expected)),
+        }
+    }
+
+    fn finish(self) -> Result<(), SceneError> {
+        match self.used.iter().position(|used| !used) {
+            Some(index) => {
+                let entry = &self.table.entries[index];
+                Err(SceneError::new(
+                    entry.line,
+                    format!("unknown key `{}` in {}", entry.key, self.table.header()),
+                ))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the line and message of the error text gives, panics if it parses
+    fn error(text: &str) -> SceneError {
+        match Scene::parse(text) {
+            Ok(_) => panic!("parsed:\n{text}"),
+            Err(err) => err,
+        }
+    }
+
+    fn assert_error(text: &str, line: usize, message: &str) {
+        let err = error(text);
+        assert_eq!(err.line, line, "{err}");
+        assert!(err.message.contains(message), "expected `{message}` in `{err}`");
+    }
+
+    #[test]
+    fn malformed_header() {
+        assert_error("[world]\ngravity = [0.0, 9.81]\n\n[[ball]\n", 4, "malformed table header");
+        assert_error("[world\n", 1, "malformed table header");
+        assert_error("[wor ld]\n", 1, "malformed table header");
+    }
+
+    #[test]
+    fn duplicate_key() {
+        assert_error("[world]\nseed = 1\n# again\nseed = 2\n", 4, "`seed` is set twice");
+    }
+
+    #[test]
+    fn unknown_key() {
+        assert_error("[world]\ngravity = [0.0, 9.81]\ngravty = [0.0, 1.0]\n", 3, "unknown key `gravty` in [world]");
+        assert_error("[[ball]]\npos = [1.0, 1.0]\ncolour = \"red\"\n", 3, "unknown key `colour` in [[ball]]");
+    }
+
+    #[test]
+    fn unknown_table() {
+        assert_error("[world]\n\n[wrold]\n", 3, "wrold");
+    }
+
+    #[test]
+    fn wrong_type() {
+        assert_error("[world]\nsleeping = 1\n", 2, "`sleeping` has to be");
+        assert_error("[world]\n\ngravity = \"down\"\n", 3, "found a string");
+        assert_error("[random_balls]\ncount = 2.5\n", 2, "a whole non-negative number");
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_error("[[material]]\nname = \"glass\"\nrestitution = 1.5\n", 3, "`restitution` has to be between 0 and 1");
+        assert_error("[player]\npos = [1.0, 1.0]\nmass = -2.0\n", 3, "`mass` has to be positive");
+        assert_error("[world]\nhz = 0\n", 2, "`hz` has to be positive");
+        assert_error("[world]\nmax_steps = 0\n", 2, "`max_steps` has to be at least 1");
+    }
+
+    #[test]
+    fn unterminated_string_or_array() {
+        assert_error("[world]\nfloor_material = \"stone\n", 2, "unterminated string");
+        assert_error("[world]\ngravity = [0.0, 9.81\n", 2, "unterminated array");
+        assert_error("[world]\ngravity = [0.0\n, 9.81]\n", 2, "unterminated array");
+    }
+
+    #[test]
+    fn key_outside_a_table() {
+        assert_error("# no table yet\nseed = 1\n", 2, "has to be inside a [table]");
+    }
+
+    #[test]
+    fn unknown_name() {
+        assert_error("[world]\nintegrator = \"leapfrog\"\n", 2, "unknown integrator `leapfrog`, expected one of");
+        assert_error("[world]\nfloor_material = \"cheese\"\n", 2, "unknown material `cheese`");
+    }
+
+    #[test]
+    fn comments_and_strings() {
+        let scene = Scene::parse("[world] # the world\nfloor_material = \"ice\" # slippery\n").unwrap();
+        assert_eq!(scene.edges.floor, Material::ICE);
+    }
+
+    #[test]
+    fn every_bundled_scene_parses() {
+        for entry in fs::read_dir("assets/scenes").unwrap() {
+            let path = entry.unwrap().path();
+            let path = path.to_str().unwrap();
+            if let Err(err) = Scene::load(path) {
+                panic!("{err}");
+            }
+        }
+    }
+}
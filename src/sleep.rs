@@ -0,0 +1,70 @@
+// Bodies that have been resting for a while stop being simulated until
+// something touches them. Bodies that rest on each other form an island
+// and fall asleep together, and wake together, so a settled pile never
+// ends up half asleep with its top hanging in the air.
+
+// in J/kg, a body whose kinetic energy per kg stays below this rests. one
+// step of gravity at 60 Hz stays below it, so a resting contact that misses
+// a step doesn't keep a whole pile awake
+pub const SLEEP_ENERGY: f32 = 0.02;
+// in s, how long a whole island has to rest before it falls asleep
+pub const SLEEP_TIME: f32 = 0.5;
+// in m/s², a ball touching nothing only rests while the forces on it cancel
+// out to below this, drag aside. not while it turns around on its way up
+// through water, or sinks slowly through oil
+pub const SLEEP_ACCELERATION: f32 = 0.2;
+
+// kinetic energy per kg, inertia in kg·m²
+pub fn energy(velocity: raylib::Vector2, angular_velocity: f32, mass: f32, inertia: f32) -> f32 {
+    (velocity.length_squared() + inertia / mass * angular_velocity * angular_velocity) / 2.0
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sleep {
+    pub idle: f32,           // in s, how long the body has been resting
+    pub island: Option<u32>, // the island it fell asleep with, None while awake
+}
+
+impl Sleep {
+    pub fn is_asleep(&self) -> bool {
+        self.island.is_some()
+    }
+
+    pub fn wake(&mut self) {
+        *self = Sleep::default();
+    }
+
+    // energy is the kinetic energy per kg, linear and angular
+    pub fn update(&mut self, energy: f32, dt: f32) {
+        if energy < SLEEP_ENERGY {
+            self.idle += dt;
+        } else {
+            self.idle = 0.0;
+        }
+    }
+}
+
+// Union find over the bodies of a step, see Solver::index.
+pub struct Islands {
+    parent: Vec<usize>,
+}
+
+impl Islands {
+    pub fn new(len: usize) -> Self {
+        Islands { parent: (0..len).collect() }
+    }
+
+    pub fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    pub fn join(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        // the lower index is the root, so islands don't depend on order
+        self.parent[a.max(b)] = a.min(b);
+    }
+}
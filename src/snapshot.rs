@@ -0,0 +1,902 @@
+// Complete copies of a World's simulation state, written either as a compact
+// binary file or as text with one `key value` per line:
+//
+//     physim snapshot <version>
+//     bounds 26.666666 20
+//     gravity 0 9.81
+//     ...
+//
+// Floats are stored so they read back bit for bit, a restored world steps
+// exactly like the one it was taken from. The scene isn't part of it, R
+// still resets to whatever scene is loaded.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use crate::ball::Ball;
+use crate::block::Block;
+use crate::broadphase::BroadphaseKind;
+use crate::collider::Collider;
+use crate::field::{Falloff, Field, FieldKind};
+use crate::fluid::{Fluid, Pool};
+use crate::hoop::Hoop;
+use crate::integrator::Integrator;
+use crate::joint::{Joint, JointKind};
+use crate::liquid::Liquid;
+use crate::material::{Combine, Material};
+use crate::nbody::Gravitation;
+use crate::player::{Player, PlayerInput};
+use crate::polygon::Polygon;
+use crate::sleep::Sleep;
+use crate::soft::SoftBody;
+use crate::solver::{BodyId, CachedImpulse, SolverSettings};
+use crate::world::World;
+use aid::Rng;
+
+// bump whenever a field is added, removed or reordered
+pub const SNAPSHOT_VERSION: u32 = 18;
+
+const MAGIC: &[u8; 8] = b"PHYSNAP\0";
+const TEXT_HEADER: &str = "physim snapshot";
+const NO_COLOR: raylib::Color = raylib::Color { r: 0, g: 0, b: 0, a: 0 };
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Binary,
+    Text,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Binary => "physnap",
+            Format::Text => "txt",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Snapshot {
+    pub bounds: raylib::Vector2,  // in m
+    pub gravity: raylib::Vector2, // in m/s²
+    pub fields: Vec<Field>,
+    pub air: Fluid,
+    pub pools: Vec<Pool>,
+    pub gravitation: Option<Gravitation>,
+    pub colliders: Vec<Collider>, // walls included
+    pub seed: u64,
+    pub rng: Rng,
+    pub tick: u64,
+    pub time: f64,        // in s
+    pub hz: f32,          // of the fixed timestep
+    pub max_steps: u32,   // of the fixed timestep
+    pub accumulator: f32, // in s, what the timestep hasn't simulated yet
+    pub broadphase: BroadphaseKind,
+    pub solver: SolverSettings,
+    pub impulses: Vec<CachedImpulse>, // for warm starting the next step
+    pub integrator: Integrator,
+    pub sleeping: bool,
+    pub next_island: u32,
+    pub balls: Vec<Ball>,
+    pub player: Player,
+    pub hoops: Vec<Hoop>,
+    pub blocks: Vec<Block>,
+    pub polygons: Vec<Polygon>,
+    pub joints: Vec<Joint>,
+    pub soft_bodies: Vec<SoftBody>,
+    pub liquids: Vec<Liquid>,
+}
+
+impl Snapshot {
+    pub fn capture(world: &World) -> Self {
+        Snapshot {
+            bounds: world.bounds,
+            gravity: world.gravity,
+            fields: world.fields.clone(),
+            air: world.air,
+            pools: world.pools.clone(),
+            gravitation: world.gravitation,
+            colliders: world.colliders.clone(),
+            seed: world.seed,
+            rng: world.rng,
+            tick: world.tick,
+            time: world.time,
+            hz: world.timestep.hz,
+            max_steps: world.timestep.max_steps,
+            accumulator: world.timestep.accumulator,
+            broadphase: world.broadphase.kind,
+            solver: world.solver.settings,
+            impulses: world.solver.cache.clone(),
+            integrator: world.integrator,
+            sleeping: world.sleeping,
+            next_island: world.next_island,
+            balls: world.balls.clone(),
+            player: world.player,
+            hoops: world.hoops.clone(),
+            blocks: world.blocks.clone(),
+            polygons: world.polygons.clone(),
+            joints: world.joints.clone(),
+            soft_bodies: world.soft_bodies.clone(),
+            liquids: world.liquids.clone(),
+        }
+    }
+
+    pub fn restore(self, world: &mut World) {
+        world.bounds = self.bounds;
+        world.gravity = self.gravity;
+        world.fields = self.fields;
+        world.air = self.air;
+        world.pools = self.pools;
+        world.gravitation = self.gravitation;
+        world.colliders = self.colliders;
+        world.seed = self.seed;
+        world.rng = self.rng;
+        world.tick = self.tick;
+        world.time = self.time;
+        world.timestep.hz = self.hz;
+        world.timestep.max_steps = self.max_steps;
+        world.timestep.accumulator = self.accumulator;
+        world.broadphase.kind = self.broadphase;
+        world.solver.settings = self.solver;
+        world.solver.cache = self.impulses;
+        world.integrator = self.integrator;
+        world.sleeping = self.sleeping;
+        world.next_island = self.next_island;
+        world.balls = self.balls;
+        world.player = self.player;
+        world.hoops = self.hoops;
+        world.blocks = self.blocks;
+        world.polygons = self.polygons;
+        world.joints = self.joints;
+        world.soft_bodies = self.soft_bodies;
+        world.liquids = self.liquids;
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = BinaryWriter { bytes: MAGIC.to_vec() };
+        writer.bytes.extend(SNAPSHOT_VERSION.to_le_bytes());
+        // writing can't fail
+        let _ = self.clone().archive(&mut writer);
+        writer.bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = BinaryReader { bytes, at: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err("not a snapshot".to_string());
+        }
+        let mut version = 0;
+        reader.u32("version", &mut version)?;
+        check_version(version)?;
+
+        let mut snapshot = Snapshot::blank();
+        snapshot.archive(&mut reader)?;
+        if reader.at != bytes.len() {
+            return Err(format!("{} bytes left over", bytes.len() - reader.at));
+        }
+        Ok(snapshot)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut writer = TextWriter { text: format!("{TEXT_HEADER} {SNAPSHOT_VERSION}\n") };
+        let _ = self.clone().archive(&mut writer);
+        writer.text
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut reader = TextReader { lines: text.lines().enumerate(), line: 0, left: text.lines().count() };
+        let (line, header) = reader.next_line()?;
+        let version = header
+            .strip_prefix(TEXT_HEADER)
+            .and_then(|version| version.trim().parse::<u32>().ok())
+            .ok_or(format!("line {line}: not a snapshot"))?;
+        check_version(version)?;
+
+        let mut snapshot = Snapshot::blank();
+        snapshot.archive(&mut reader)?;
+        if let Ok((line, _)) = reader.next_line() {
+            return Err(format!("line {line}: expected the end of the snapshot"));
+        }
+        Ok(snapshot)
+    }
+
+    pub fn save(&self, path: &Path, format: Format) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+        }
+        let result = match format {
+            Format::Binary => fs::write(path, self.to_bytes()),
+            Format::Text => fs::write(path, self.to_text()),
+        };
+        result.map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    // either format, told apart by the first bytes
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|err| format!("{}: {err}", path.display()))?;
+        let snapshot = if bytes.starts_with(MAGIC) {
+            Snapshot::from_bytes(&bytes)
+        } else {
+            match std::str::from_utf8(&bytes) {
+                Ok(text) => Snapshot::from_text(text),
+                Err(_) => Err("not a snapshot".to_string()),
+            }
+        };
+        snapshot.map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    // whether path starts like either format, so a dropped .txt snapshot
+    // isn't taken for a scene
+    pub fn is_snapshot(path: &Path) -> bool {
+        let Ok(bytes) = fs::read(path) else {
+            return false;
+        };
+        if bytes.starts_with(MAGIC) {
+            return true;
+        }
+        let text = String::from_utf8_lossy(&bytes);
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+        lines.next().is_some_and(|line| line.starts_with(TEXT_HEADER))
+    }
+
+    // what a reader starts from, every field gets overwritten
+    fn blank() -> Self {
+        Snapshot {
+            bounds: raylib::Vector2::zero(),
+            gravity: raylib::Vector2::zero(),
+            fields: Vec::new(),
+            air: Fluid::default(),
+            pools: Vec::new(),
+            gravitation: None,
+            colliders: Vec::new(),
+            seed: 0,
+            rng: Rng::new(0),
+            tick: 0,
+            time: 0.0,
+            hz: 0.0,
+            max_steps: 0,
+            accumulator: 0.0,
+            broadphase: BroadphaseKind::SpatialHash,
+            solver: SolverSettings::default(),
+            impulses: Vec::new(),
+            integrator: Integrator::default(),
+            sleeping: true,
+            next_island: 0,
+            balls: Vec::new(),
+            player: Player::new(),
+            hoops: Vec::new(),
+            blocks: Vec::new(),
+            polygons: Vec::new(),
+            joints: Vec::new(),
+            soft_bodies: Vec::new(),
+            liquids: Vec::new(),
+        }
+    }
+
+    // the one place the layout is defined, both directions go through here
+    fn archive<A: Archive>(&mut self, a: &mut A) -> Result<(), String> {
+        a.vec2("bounds", &mut self.bounds)?;
+        a.vec2("gravity", &mut self.gravity)?;
+        a.u64("seed", &mut self.seed)?;
+        a.u64("rng_state", &mut self.rng.state)?;
+        a.u64("rng_inc", &mut self.rng.inc)?;
+        a.u64("tick", &mut self.tick)?;
+        a.f64("time", &mut self.time)?;
+        a.f32("hz", &mut self.hz)?;
+        if !(self.hz > 0.0 && self.hz.is_finite()) {
+            return Err(format!("hz {}, has to be above 0", self.hz));
+        }
+        a.u32("max_steps", &mut self.max_steps)?;
+        if self.max_steps == 0 {
+            return Err("max_steps 0, has to be at least 1".to_string());
+        }
+        a.f32("accumulator", &mut self.accumulator)?;
+
+        let mut broadphase = self.broadphase as u8;
+        a.u8("broadphase", &mut broadphase)?;
+        self.broadphase = match broadphase {
+            0 => BroadphaseKind::SpatialHash,
+            1 => BroadphaseKind::SweepAndPrune,
+            2 => BroadphaseKind::BruteForce,
+            _ => return Err(format!("unknown broadphase {broadphase}")),
+        };
+        a.u32("velocity_iterations", &mut self.solver.velocity_iterations)?;
+        a.u32("position_iterations", &mut self.solver.position_iterations)?;
+        a.bool("warm_starting", &mut self.solver.warm_starting)?;
+        let mut integrator = self.integrator as u8;
+        a.u8("integrator", &mut integrator)?;
+        self.integrator = *Integrator::ALL.get(integrator as usize).ok_or(format!("unknown integrator {integrator}"))?;
+        a.bool("sleeping", &mut self.sleeping)?;
+        a.u32("next_island", &mut self.next_island)?;
+        archive_gravitation(a, &mut self.gravitation)?;
+        a.f32("air_density", &mut self.air.density)?;
+        a.f32("air_viscosity", &mut self.air.viscosity)?;
+
+        archive_player(a, &mut self.player)?;
+
+        let blank_ball = Ball {
+            pos: raylib::Vector2::zero(),
+            prev_pos: raylib::Vector2::zero(),
+            velocity: raylib::Vector2::zero(),
+            drift: raylib::Vector2::zero(),
+            angle: 0.0,
+            prev_angle: 0.0,
+            angular_velocity: 0.0,
+            radius: 0.0,
+            mass: 0.0,
+            material: Material::RUBBER,
+            color: NO_COLOR,
+            bullet: false,
+            sleep: Sleep::default(),
+        };
+        archive_list(a, "balls", &mut self.balls, blank_ball, archive_ball)?;
+        archive_list(a, "hoops", &mut self.hoops, Hoop::new(), archive_hoop)?;
+        let blank_block = Block::new_static(raylib::Vector2::zero(), raylib::Vector2::zero());
+        archive_list(a, "blocks", &mut self.blocks, blank_block, archive_block)?;
+        let blank_polygon = Polygon::rect(raylib::Vector2::zero(), raylib::Vector2::one(), 0.0, Material::WOOD);
+        archive_list(a, "polygons", &mut self.polygons, blank_polygon, archive_polygon)?;
+        let blank_joint = Joint::between(JointKind::Distance, raylib::Vector2::zero(), raylib::Vector2::zero());
+        archive_list(a, "joints", &mut self.joints, blank_joint, archive_joint)?;
+        let counts = (self.balls.len(), self.blocks.len(), self.polygons.len());
+        if let Some(joint) = self.joints.iter().find(|joint| !exists(joint.a, counts) || !exists(joint.b, counts)) {
+            return Err(format!("joint between {:?} and {:?}, which aren't there", joint.a, joint.b));
+        }
+        let blank_soft = SoftBody {
+            points: Vec::new(),
+            rest: Vec::new(),
+            outline: Vec::new(),
+            area: 0.0,
+            pressure: 0.0,
+            shape_stiffness: 0.0,
+            color: NO_COLOR,
+        };
+        archive_list(a, "soft_bodies", &mut self.soft_bodies, blank_soft, archive_soft_body)?;
+        if self.soft_bodies.iter().flat_map(|soft| soft.points.iter()).any(|&i| i >= self.balls.len()) {
+            return Err("soft body with a point that isn't there".to_string());
+        }
+        archive_list(a, "liquids", &mut self.liquids, Liquid::new(), archive_liquid)?;
+        if self.liquids.iter().flat_map(|liquid| liquid.particles.iter()).any(|&i| i >= self.balls.len()) {
+            return Err("liquid with a particle that isn't there".to_string());
+        }
+        let blank_collider = Collider::segment(raylib::Vector2::zero(), raylib::Vector2::zero());
+        archive_list(a, "colliders", &mut self.colliders, blank_collider, archive_collider)?;
+        let blank_field = Field::uniform(raylib::Vector2::zero());
+        archive_list(a, "fields", &mut self.fields, blank_field, archive_field)?;
+        let blank_pool = Pool::new(raylib::Rectangle { x: 0.0, y: 0.0, width: 0.0, height: 0.0 });
+        archive_list(a, "pools", &mut self.pools, blank_pool, archive_pool)?;
+        let blank_impulse = CachedImpulse {
+            a: BodyId::Fixed,
+            b: BodyId::Fixed,
+            offset: raylib::Vector2::zero(),
+            normal: 0.0,
+            tangent: 0.0,
+            rolling: 0.0,
+        };
+        archive_list(a, "impulses", &mut self.impulses, blank_impulse, archive_impulse)?;
+
+        Ok(())
+    }
+}
+
+fn check_version(version: u32) -> Result<(), String> {
+    if version != SNAPSHOT_VERSION {
+        return Err(format!("snapshot version {version}, expected {SNAPSHOT_VERSION}"));
+    }
+    Ok(())
+}
+
+fn archive_list<A: Archive, T: Clone>(
+    a: &mut A,
+    name: &str,
+    items: &mut Vec<T>,
+    blank: T,
+    archive_item: fn(&mut A, &mut T) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut len = items.len() as u64;
+    a.u64(name, &mut len)?;
+    if len > a.remaining() as u64 {
+        return Err(format!("{len} {name}, more than there's room for"));
+    }
+    items.resize(len as usize, blank);
+    for item in items.iter_mut() {
+        archive_item(a, item)?;
+    }
+    Ok(())
+}
+
+// prefix is the owner, empty inside a list entry
+fn archive_material<A: Archive>(a: &mut A, prefix: &str, material: &mut Material) -> Result<(), String> {
+    let key = |name: &str| if prefix.is_empty() { name.to_string() } else { format!("{prefix}_{name}") };
+    a.f32(&key("restitution"), &mut material.restitution)?;
+    a.f32(&key("static_friction"), &mut material.friction.static_coef)?;
+    a.f32(&key("friction"), &mut material.friction.dynamic_coef)?;
+    a.f32(&key("rolling_friction"), &mut material.friction.rolling)?;
+    a.f32(&key("density"), &mut material.density)?;
+    archive_combine(a, &key("restitution_combine"), &mut material.restitution_combine)?;
+    archive_combine(a, &key("friction_combine"), &mut material.friction_combine)
+}
+
+fn archive_combine<A: Archive>(a: &mut A, name: &str, combine: &mut Combine) -> Result<(), String> {
+    let mut index = Combine::ALL.iter().position(|rule| rule == combine).unwrap_or(0) as u8;
+    a.u8(name, &mut index)?;
+    *combine = *Combine::ALL.get(index as usize).ok_or(format!("unknown combine rule {index}"))?;
+    Ok(())
+}
+
+fn archive_ball<A: Archive>(a: &mut A, ball: &mut Ball) -> Result<(), String> {
+    a.vec2("pos", &mut ball.pos)?;
+    a.vec2("prev_pos", &mut ball.prev_pos)?;
+    a.vec2("velocity", &mut ball.velocity)?;
+    a.f32("angle", &mut ball.angle)?;
+    a.f32("prev_angle", &mut ball.prev_angle)?;
+    a.f32("angular_velocity", &mut ball.angular_velocity)?;
+    a.f32("radius", &mut ball.radius)?;
+    a.f32("mass", &mut ball.mass)?;
+    archive_material(a, "", &mut ball.material)?;
+    a.color("color", &mut ball.color)?;
+    a.bool("bullet", &mut ball.bullet)?;
+    archive_sleep(a, &mut ball.sleep)
+}
+
+fn archive_player<A: Archive>(a: &mut A, player: &mut Player) -> Result<(), String> {
+    a.vec2("player_pos", &mut player.pos)?;
+    a.vec2("player_prev_pos", &mut player.prev_pos)?;
+    a.vec2("player_dim", &mut player.dim)?;
+    a.vec2("player_velocity", &mut player.velocity)?;
+    a.f32("player_angle", &mut player.angle)?;
+    a.f32("player_prev_angle", &mut player.prev_angle)?;
+    a.f32("player_angular_velocity", &mut player.angular_velocity)?;
+    a.f32("player_mass", &mut player.mass)?;
+    archive_material(a, "player", &mut player.material)?;
+    a.color("player_color", &mut player.color)?;
+    a.f32("player_idle", &mut player.sleep.idle)?;
+
+    let input: &mut PlayerInput = &mut player.input;
+    a.bool("input_left", &mut input.left)?;
+    a.bool("input_right", &mut input.right)?;
+    a.bool("input_jump", &mut input.jump)?;
+    a.bool("input_slam", &mut input.slam)
+}
+
+fn archive_hoop<A: Archive>(a: &mut A, hoop: &mut Hoop) -> Result<(), String> {
+    a.vec2("pos", &mut hoop.pos)?;
+    a.vec2("dim", &mut hoop.dim)?;
+    a.color("color", &mut hoop.color)?;
+    archive_material(a, "", &mut hoop.material)?;
+    a.bool("follow_edge", &mut hoop.follow_edge)
+}
+
+fn archive_block<A: Archive>(a: &mut A, block: &mut Block) -> Result<(), String> {
+    a.vec2("pos", &mut block.pos)?;
+    a.vec2("prev_pos", &mut block.prev_pos)?;
+    a.vec2("dim", &mut block.dim)?;
+    a.vec2("velocity", &mut block.velocity)?;
+    a.f32("mass", &mut block.mass)?;
+    archive_material(a, "", &mut block.material)?;
+    a.color("color", &mut block.color)?;
+    archive_sleep(a, &mut block.sleep)
+}
+
+fn archive_polygon<A: Archive>(a: &mut A, polygon: &mut Polygon) -> Result<(), String> {
+    a.vec2("pos", &mut polygon.pos)?;
+    a.vec2("prev_pos", &mut polygon.prev_pos)?;
+    a.vec2("velocity", &mut polygon.velocity)?;
+    a.f32("angle", &mut polygon.angle)?;
+    a.f32("prev_angle", &mut polygon.prev_angle)?;
+    a.f32("angular_velocity", &mut polygon.angular_velocity)?;
+    archive_list(a, "points", &mut polygon.points, raylib::Vector2::zero(), |a, point| a.vec2("point", point))?;
+    if polygon.points.len() < 3 {
+        return Err(format!("polygon with {} points", polygon.points.len()));
+    }
+    a.f32("mass", &mut polygon.mass)?;
+    archive_material(a, "", &mut polygon.material)?;
+    a.color("color", &mut polygon.color)?;
+    archive_sleep(a, &mut polygon.sleep)
+}
+
+fn archive_soft_body<A: Archive>(a: &mut A, soft: &mut SoftBody) -> Result<(), String> {
+    archive_list(a, "points", &mut soft.points, 0, archive_index)?;
+    archive_list(a, "rest", &mut soft.rest, raylib::Vector2::zero(), |a, rest| a.vec2("rest", rest))?;
+    archive_list(a, "outline", &mut soft.outline, 0, archive_index)?;
+    if soft.rest.len() != soft.points.len() {
+        return Err(format!("soft body with {} points and {} rest positions", soft.points.len(), soft.rest.len()));
+    }
+    if soft.outline.len() < 3 || soft.outline.iter().any(|&k| k >= soft.points.len()) {
+        return Err(format!("soft body with an outline of {:?}", soft.outline));
+    }
+    a.f32("area", &mut soft.area)?;
+    a.f32("pressure", &mut soft.pressure)?;
+    a.f32("shape_stiffness", &mut soft.shape_stiffness)?;
+    a.color("color", &mut soft.color)
+}
+
+fn archive_liquid<A: Archive>(a: &mut A, liquid: &mut Liquid) -> Result<(), String> {
+    archive_list(a, "particles", &mut liquid.particles, 0, archive_index)?;
+    archive_list(a, "densities", &mut liquid.densities, 0.0, |a, density| a.f32("density", density))?;
+    if liquid.densities.len() != liquid.particles.len() {
+        return Err(format!("liquid with {} particles and {} densities", liquid.particles.len(), liquid.densities.len()));
+    }
+    a.f32("spacing", &mut liquid.spacing)?;
+    a.f32("density", &mut liquid.density)?;
+    a.f32("viscosity", &mut liquid.viscosity)?;
+    a.color("color", &mut liquid.color)
+}
+
+fn archive_index<A: Archive>(a: &mut A, index: &mut usize) -> Result<(), String> {
+    let mut value = *index as u64;
+    a.u64("index", &mut value)?;
+    *index = value as usize;
+    Ok(())
+}
+
+fn archive_sleep<A: Archive>(a: &mut A, sleep: &mut Sleep) -> Result<(), String> {
+    a.f32("idle", &mut sleep.idle)?;
+    let mut asleep = sleep.island.is_some();
+    let mut island = sleep.island.unwrap_or(0);
+    a.bool("asleep", &mut asleep)?;
+    a.u32("island", &mut island)?;
+    sleep.island = asleep.then_some(island);
+    Ok(())
+}
+
+fn archive_collider<A: Archive>(a: &mut A, collider: &mut Collider) -> Result<(), String> {
+    archive_list(a, "points", &mut collider.points, raylib::Vector2::zero(), |a, point| a.vec2("point", point))?;
+    if collider.points.len() < 2 {
+        return Err(format!("collider with {} points", collider.points.len()));
+    }
+    archive_material(a, "", &mut collider.material)?;
+    a.color("color", &mut collider.color)
+}
+
+fn archive_impulse<A: Archive>(a: &mut A, impulse: &mut CachedImpulse) -> Result<(), String> {
+    archive_body(a, "a", &mut impulse.a)?;
+    archive_body(a, "b", &mut impulse.b)?;
+    a.vec2("offset", &mut impulse.offset)?;
+    a.f32("normal", &mut impulse.normal)?;
+    a.f32("tangent", &mut impulse.tangent)?;
+    a.f32("rolling", &mut impulse.rolling)
+}
+
+fn archive_joint<A: Archive>(a: &mut A, joint: &mut Joint) -> Result<(), String> {
+    let mut kind = JointKind::ALL.iter().position(|kind| *kind == joint.kind).unwrap_or(0) as u8;
+    a.u8("kind", &mut kind)?;
+    joint.kind = *JointKind::ALL.get(kind as usize).ok_or(format!("unknown joint kind {kind}"))?;
+    archive_body(a, "a", &mut joint.a)?;
+    archive_body(a, "b", &mut joint.b)?;
+    a.vec2("anchor_a", &mut joint.anchor_a)?;
+    a.vec2("anchor_b", &mut joint.anchor_b)?;
+    a.f32("length", &mut joint.length)?;
+    a.f32("stiffness", &mut joint.stiffness)?;
+    a.f32("damping", &mut joint.damping)?;
+    a.vec2("impulse", &mut joint.impulse)
+}
+
+fn archive_field<A: Archive>(a: &mut A, field: &mut Field) -> Result<(), String> {
+    let mut kind = FieldKind::ALL.iter().position(|kind| *kind == field.kind).unwrap_or(0) as u8;
+    a.u8("kind", &mut kind)?;
+    field.kind = *FieldKind::ALL.get(kind as usize).ok_or(format!("unknown field kind {kind}"))?;
+    a.vec2("acceleration", &mut field.acceleration)?;
+    a.vec2("center", &mut field.center)?;
+    a.f32("strength", &mut field.strength)?;
+    let mut falloff = Falloff::ALL.iter().position(|falloff| *falloff == field.falloff).unwrap_or(0) as u8;
+    a.u8("falloff", &mut falloff)?;
+    field.falloff = *Falloff::ALL.get(falloff as usize).ok_or(format!("unknown falloff {falloff}"))?;
+    a.f32("radius", &mut field.radius)?;
+
+    let region = field.region.unwrap_or(raylib::Rectangle { x: 0.0, y: 0.0, width: 0.0, height: 0.0 });
+    let mut has_region = field.region.is_some();
+    let mut pos = raylib::Vector2::new(region.x, region.y);
+    let mut dim = raylib::Vector2::new(region.width, region.height);
+    a.bool("has_region", &mut has_region)?;
+    a.vec2("region_pos", &mut pos)?;
+    a.vec2("region_dim", &mut dim)?;
+    field.region = has_region.then_some(raylib::Rectangle { x: pos.x, y: pos.y, width: dim.x, height: dim.y });
+    Ok(())
+}
+
+fn archive_pool<A: Archive>(a: &mut A, pool: &mut Pool) -> Result<(), String> {
+    let mut pos = raylib::Vector2::new(pool.rect.x, pool.rect.y);
+    let mut dim = raylib::Vector2::new(pool.rect.width, pool.rect.height);
+    a.vec2("pos", &mut pos)?;
+    a.vec2("dim", &mut dim)?;
+    pool.rect = raylib::Rectangle { x: pos.x, y: pos.y, width: dim.x, height: dim.y };
+    a.f32("density", &mut pool.fluid.density)?;
+    a.f32("viscosity", &mut pool.fluid.viscosity)?;
+    a.color("color", &mut pool.color)
+}
+
+fn archive_gravitation<A: Archive>(a: &mut A, gravitation: &mut Option<Gravitation>) -> Result<(), String> {
+    let mut has_gravitation = gravitation.is_some();
+    let mut value = gravitation.unwrap_or_default();
+    a.bool("has_gravitation", &mut has_gravitation)?;
+    a.f32("gravitational_constant", &mut value.constant)?;
+    a.f32("softening", &mut value.softening)?;
+    a.f32("theta", &mut value.theta)?;
+    a.bool("merging", &mut value.merging)?;
+    *gravitation = has_gravitation.then_some(value);
+    Ok(())
+}
+
+// whether body is one of this many balls, blocks and polygons
+fn exists(body: BodyId, (balls, blocks, polygons): (usize, usize, usize)) -> bool {
+    match body {
+        BodyId::Ball(i) => i < balls,
+        BodyId::Block(i) => i < blocks,
+        BodyId::Polygon(i) => i < polygons,
+        BodyId::Player | BodyId::Fixed => true,
+    }
+}
+
+// a kind and an index, which only the lists use
+fn archive_body<A: Archive>(a: &mut A, name: &str, body: &mut BodyId) -> Result<(), String> {
+    let (mut kind, mut index) = match *body {
+        BodyId::Ball(i) => (0, i as u64),
+        BodyId::Block(i) => (1, i as u64),
+        BodyId::Polygon(i) => (2, i as u64),
+        BodyId::Player => (3, 0),
+        BodyId::Fixed => (4, 0),
+    };
+    a.u8(&format!("{name}_kind"), &mut kind)?;
+    a.u64(&format!("{name}_index"), &mut index)?;
+    let index = index as usize;
+    *body = match kind {
+        0 => BodyId::Ball(index),
+        1 => BodyId::Block(index),
+        2 => BodyId::Polygon(index),
+        3 => BodyId::Player,
+        4 => BodyId::Fixed,
+        _ => return Err(format!("unknown body kind {kind}")),
+    };
+    Ok(())
+}
+
+// Writers read the value and ignore the error path, readers overwrite it.
+// Names only show up in the text format.
+trait Archive {
+    fn u8(&mut self, name: &str, value: &mut u8) -> Result<(), String>;
+    fn u32(&mut self, name: &str, value: &mut u32) -> Result<(), String>;
+    fn u64(&mut self, name: &str, value: &mut u64) -> Result<(), String>;
+    fn f32(&mut self, name: &str, value: &mut f32) -> Result<(), String>;
+    fn f64(&mut self, name: &str, value: &mut f64) -> Result<(), String>;
+    fn bool(&mut self, name: &str, value: &mut bool) -> Result<(), String>;
+    fn vec2(&mut self, name: &str, value: &mut raylib::Vector2) -> Result<(), String>;
+    fn color(&mut self, name: &str, value: &mut raylib::Color) -> Result<(), String>;
+    // how many bytes or lines are still to read, every list entry takes at
+    // least one, so a list can't be any longer
+    fn remaining(&self) -> usize;
+}
+
+// Binary: little endian, floats as their raw bits
+
+struct BinaryWriter {
+    bytes: Vec<u8>,
+}
+
+impl Archive for BinaryWriter {
+    fn u8(&mut self, _name: &str, value: &mut u8) -> Result<(), String> {
+        self.bytes.push(*value);
+        Ok(())
+    }
+    fn u32(&mut self, _name: &str, value: &mut u32) -> Result<(), String> {
+        self.bytes.extend(value.to_le_bytes());
+        Ok(())
+    }
+    fn u64(&mut self, _name: &str, value: &mut u64) -> Result<(), String> {
+        self.bytes.extend(value.to_le_bytes());
+        Ok(())
+    }
+    fn f32(&mut self, name: &str, value: &mut f32) -> Result<(), String> {
+        self.u32(name, &mut value.to_bits())
+    }
+    fn f64(&mut self, name: &str, value: &mut f64) -> Result<(), String> {
+        self.u64(name, &mut value.to_bits())
+    }
+    fn bool(&mut self, name: &str, value: &mut bool) -> Result<(), String> {
+        self.u8(name, &mut (*value as u8))
+    }
+    fn vec2(&mut self, name: &str, value: &mut raylib::Vector2) -> Result<(), String> {
+        self.f32(name, &mut value.x)?;
+        self.f32(name, &mut value.y)
+    }
+    fn color(&mut self, _name: &str, value: &mut raylib::Color) -> Result<(), String> {
+        self.bytes.extend([value.r, value.g, value.b, value.a]);
+        Ok(())
+    }
+    fn remaining(&self) -> usize {
+        usize::MAX
+    }
+}
+
+struct BinaryReader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> BinaryReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self.bytes.get(self.at..self.at + len).ok_or("snapshot is cut short")?;
+        self.at += len;
+        Ok(bytes)
+    }
+}
+
+impl Archive for BinaryReader<'_> {
+    fn u8(&mut self, _name: &str, value: &mut u8) -> Result<(), String> {
+        *value = self.take(1)?[0];
+        Ok(())
+    }
+    fn u32(&mut self, _name: &str, value: &mut u32) -> Result<(), String> {
+        *value = u32::from_le_bytes(self.take(4)?.try_into().unwrap());
+        Ok(())
+    }
+    fn u64(&mut self, _name: &str, value: &mut u64) -> Result<(), String> {
+        *value = u64::from_le_bytes(self.take(8)?.try_into().unwrap());
+        Ok(())
+    }
+    fn f32(&mut self, name: &str, value: &mut f32) -> Result<(), String> {
+        let mut bits = 0;
+        self.u32(name, &mut bits)?;
+        *value = f32::from_bits(bits);
+        Ok(())
+    }
+    fn f64(&mut self, name: &str, value: &mut f64) -> Result<(), String> {
+        let mut bits = 0;
+        self.u64(name, &mut bits)?;
+        *value = f64::from_bits(bits);
+        Ok(())
+    }
+    fn bool(&mut self, name: &str, value: &mut bool) -> Result<(), String> {
+        let mut byte = 0;
+        self.u8(name, &mut byte)?;
+        *value = byte != 0;
+        Ok(())
+    }
+    fn vec2(&mut self, name: &str, value: &mut raylib::Vector2) -> Result<(), String> {
+        self.f32(name, &mut value.x)?;
+        self.f32(name, &mut value.y)
+    }
+    fn color(&mut self, _name: &str, value: &mut raylib::Color) -> Result<(), String> {
+        let rgba = self.take(4)?;
+        *value = raylib::Color { r: rgba[0], g: rgba[1], b: rgba[2], a: rgba[3] };
+        Ok(())
+    }
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.at
+    }
+}
+
+// Text: Display prints the shortest float that parses back to the same
+// bits, so this round-trips as exactly as the binary format
+
+struct TextWriter {
+    text: String,
+}
+
+impl TextWriter {
+    fn line(&mut self, name: &str, value: std::fmt::Arguments) -> Result<(), String> {
+        let _ = writeln!(self.text, "{name} {value}");
+        Ok(())
+    }
+}
+
+impl Archive for TextWriter {
+    fn u8(&mut self, name: &str, value: &mut u8) -> Result<(), String> {
+        self.line(name, format_args!("{value}"))
+    }
+    fn u32(&mut self, name: &str, value: &mut u32) -> Result<(), String> {
+        self.line(name, format_args!("{value}"))
+    }
+    fn u64(&mut self, name: &str, value: &mut u64) -> Result<(), String> {
+        self.line(name, format_args!("{value}"))
+    }
+    fn f32(&mut self, name: &str, value: &mut f32) -> Result<(), String> {
+        self.line(name, format_args!("{value}"))
+    }
+    fn f64(&mut self, name: &str, value: &mut f64) -> Result<(), String> {
+        self.line(name, format_args!("{value}"))
+    }
+    fn bool(&mut self, name: &str, value: &mut bool) -> Result<(), String> {
+        self.line(name, format_args!("{value}"))
+    }
+    fn vec2(&mut self, name: &str, value: &mut raylib::Vector2) -> Result<(), String> {
+        self.line(name, format_args!("{} {}", value.x, value.y))
+    }
+    fn color(&mut self, name: &str, value: &mut raylib::Color) -> Result<(), String> {
+        self.line(name, format_args!("{} {} {} {}", value.r, value.g, value.b, value.a))
+    }
+    fn remaining(&self) -> usize {
+        usize::MAX
+    }
+}
+
+struct TextReader<'a> {
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+    line: usize, // of the last value read, for errors
+    left: usize, // lines not read yet
+}
+
+impl<'a> TextReader<'a> {
+    // skips blank lines and # comments
+    fn next_line(&mut self) -> Result<(usize, &'a str), String> {
+        for (i, line) in self.lines.by_ref() {
+            self.left -= 1;
+            let line = line.trim();
+            if !line.is_empty() && !line.starts_with('#') {
+                self.line = i + 1;
+                return Ok((i + 1, line));
+            }
+        }
+        Err(format!("line {}: snapshot is cut short", self.line + 1))
+    }
+
+    // the values after `name`, exactly `count` of them
+    fn values(&mut self, name: &str, count: usize) -> Result<Vec<&'a str>, String> {
+        let (line, text) = self.next_line()?;
+        let mut words = text.split_whitespace();
+        let key = words.next().unwrap_or_default();
+        if key != name {
+            return Err(format!("line {line}: expected `{name}`, found `{key}`"));
+        }
+        let values: Vec<&str> = words.collect();
+        if values.len() != count {
+            return Err(format!("line {line}: `{name}` takes {count} value(s), found {}", values.len()));
+        }
+        Ok(values)
+    }
+
+    fn parse<T: std::str::FromStr>(&self, name: &str, value: &str) -> Result<T, String> {
+        value
+            .parse::<T>()
+            .map_err(|_| format!("line {}: `{value}` isn't a valid {name}", self.line))
+    }
+
+    fn one<T: std::str::FromStr>(&mut self, name: &str) -> Result<T, String> {
+        let values = self.values(name, 1)?;
+        self.parse(name, values[0])
+    }
+}
+
+impl Archive for TextReader<'_> {
+    fn u8(&mut self, name: &str, value: &mut u8) -> Result<(), String> {
+        *value = self.one(name)?;
+        Ok(())
+    }
+    fn u32(&mut self, name: &str, value: &mut u32) -> Result<(), String> {
+        *value = self.one(name)?;
+        Ok(())
+    }
+    fn u64(&mut self, name: &str, value: &mut u64) -> Result<(), String> {
+        *value = self.one(name)?;
+        Ok(())
+    }
+    fn f32(&mut self, name: &str, value: &mut f32) -> Result<(), String> {
+        *value = self.one(name)?;
+        Ok(())
+    }
+    fn f64(&mut self, name: &str, value: &mut f64) -> Result<(), String> {
+        *value = self.one(name)?;
+        Ok(())
+    }
+    fn bool(&mut self, name: &str, value: &mut bool) -> Result<(), String> {
+        *value = self.one(name)?;
+        Ok(())
+    }
+    fn vec2(&mut self, name: &str, value: &mut raylib::Vector2) -> Result<(), String> {
+        let values = self.values(name, 2)?;
+        value.x = self.parse(name, values[0])?;
+        value.y = self.parse(name, values[1])?;
+        Ok(())
+    }
+    fn color(&mut self, name: &str, value: &mut raylib::Color) -> Result<(), String> {
+        let values = self.values(name, 4)?;
+        value.r = self.parse(name, values[0])?;
+        value.g = self.parse(name, values[1])?;
+        value.b = self.parse(name, values[2])?;
+        value.a = self.parse(name, values[3])?;
+        Ok(())
+    }
+    fn remaining(&self) -> usize {
+        self.left
+    }
+}
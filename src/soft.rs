@@ -0,0 +1,277 @@
+// Soft bodies are point masses held together by springs. The points are
+// small balls, so they collide with everything balls do, and the springs
+// are joints the solver handles like any other. Springs alone let a ring
+// fold flat, so a soft body also keeps its shape one of two ways, or both:
+//
+// Pressure pushes the outline out like the air in a balloon, harder the
+// smaller it gets, for blobs. Shape matching pulls every point towards where
+// it would be if the body were rigid, moved and turned the way its points
+// are on average, for jelly.
+
+use crate::ball::{Ball, PIXELS_PER_METER};
+use crate::joint::{Joint, JointKind};
+use crate::material::Material;
+use crate::solver::BodyId;
+
+pub const SOFT_POINT_RADIUS: f32 = 0.15; // in m
+pub const SOFT_STIFFNESS: f32 = 200.0; // in N/m, of the springs between the points
+pub const SOFT_DAMPING: f32 = 1.0; // in N·s/m
+pub const BLOB_PRESSURE: f32 = 3.0; // in N/m
+pub const JELLY_SHAPE_STIFFNESS: f32 = 60.0; // in 1/s²
+pub const SOFT_SIZE: f32 = 2.0; // in m, across what G drops, see Lattice::at
+
+// pressure stops growing once a body is squashed to this much of its area
+const MIN_AREA: f32 = 0.1;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LatticeKind {
+    Ring, // points around an ellipse, each held to the next two
+    Grid, // points in rows and columns, held to their neighbors and across
+}
+
+impl LatticeKind {
+    pub const ALL: [LatticeKind; 2] = [LatticeKind::Ring, LatticeKind::Grid];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LatticeKind::Ring => "ring",
+            LatticeKind::Grid => "grid",
+        }
+    }
+}
+
+// What a soft body is made of, see Lattice::build.
+#[derive(Clone, Copy, Debug)]
+pub struct Lattice {
+    pub kind: LatticeKind,
+    pub center: raylib::Vector2, // in m
+    pub size: raylib::Vector2,   // in m, across
+    pub points: usize,           // around a ring, along the longer side of a grid
+    pub radius: f32,             // in m, of every point
+    pub material: Material,
+    pub color: raylib::Color,
+    pub stiffness: f32,       // in N/m, of the springs
+    pub damping: f32,         // in N·s/m, of the springs
+    pub pressure: f32,        // in N/m at the size it's made at, 0 for none
+    pub shape_stiffness: f32, // in 1/s², 0 for no shape matching
+}
+
+impl Lattice {
+    // a round blob held up by pressure
+    pub fn ring(center: raylib::Vector2, diameter: f32) -> Self {
+        Lattice {
+            kind: LatticeKind::Ring,
+            center,
+            size: raylib::Vector2::new_from(diameter),
+            points: 16,
+            radius: SOFT_POINT_RADIUS,
+            material: Material::RUBBER,
+            color: raylib::PINK,
+            stiffness: SOFT_STIFFNESS,
+            damping: SOFT_DAMPING,
+            pressure: BLOB_PRESSURE,
+            shape_stiffness: 0.0,
+        }
+    }
+
+    // a block of jelly that keeps its shape by shape matching
+    pub fn grid(center: raylib::Vector2, size: raylib::Vector2) -> Self {
+        Lattice {
+            kind: LatticeKind::Grid,
+            size,
+            points: 5,
+            color: raylib::LIME,
+            pressure: 0.0,
+            shape_stiffness: JELLY_SHAPE_STIFFNESS,
+            ..Lattice::ring(center, 0.0)
+        }
+    }
+
+    // what G drops at center, a blob or with shift a block of jelly
+    pub fn at(kind: LatticeKind, center: raylib::Vector2) -> Self {
+        match kind {
+            LatticeKind::Ring => Lattice::ring(center, SOFT_SIZE),
+            LatticeKind::Grid => Lattice::grid(center, raylib::Vector2::new_from(SOFT_SIZE)),
+        }
+    }
+
+    // first is the index the first point gets once they're added to the balls
+    pub fn build(&self, first: usize) -> (Vec<Ball>, Vec<Joint>, SoftBody) {
+        let half = self.size / 2.0;
+        let (positions, springs, outline) = match self.kind {
+            LatticeKind::Ring => {
+                let n = self.points.max(3);
+                let positions: Vec<raylib::Vector2> = (0..n)
+                    .map(|i| {
+                        let angle = i as f32 / n as f32 * std::f32::consts::TAU;
+                        self.center + raylib::Vector2::new(angle.cos() * half.x, angle.sin() * half.y)
+                    })
+                    .collect();
+                // to the next one and the one after, which keeps it from kinking
+                let springs = (0..n).flat_map(|i| [(i, (i + 1) % n), (i, (i + 2) % n)]).collect();
+                (positions, springs, (0..n).collect())
+            }
+            LatticeKind::Grid => {
+                let spacing = self.size.x.max(self.size.y) / (self.points.max(2) - 1) as f32;
+                let columns = ((self.size.x / spacing).round() as usize + 1).max(2);
+                let rows = ((self.size.y / spacing).round() as usize + 1).max(2);
+                let step = raylib::Vector2::new(self.size.x / (columns - 1) as f32, self.size.y / (rows - 1) as f32);
+                let at = |column: usize, row: usize| row * columns + column;
+                let positions = (0..rows)
+                    .flat_map(|row| (0..columns).map(move |column| (column, row)))
+                    .map(|(column, row)| self.center - half + raylib::Vector2::new(column as f32 * step.x, row as f32 * step.y))
+                    .collect();
+
+                // right, down and both ways across every cell
+                let mut springs = Vec::new();
+                for row in 0..rows {
+                    for column in 0..columns {
+                        if column + 1 < columns {
+                            springs.push((at(column, row), at(column + 1, row)));
+                        }
+                        if row + 1 < rows {
+                            springs.push((at(column, row), at(column, row + 1)));
+                        }
+                        if column + 1 < columns && row + 1 < rows {
+                            springs.push((at(column, row), at(column + 1, row + 1)));
+                            springs.push((at(column + 1, row), at(column, row + 1)));
+                        }
+                    }
+                }
+
+                // along the top, down the right, back along the bottom, up the left
+                let top = (0..columns).map(|column| at(column, 0));
+                let right = (1..rows).map(|row| at(columns - 1, row));
+                let bottom = (0..columns - 1).rev().map(|column| at(column, rows - 1));
+                let left = (1..rows - 1).rev().map(|row| at(0, row));
+                (positions, springs, top.chain(right).chain(bottom).chain(left).collect())
+            }
+        };
+
+        let balls: Vec<Ball> =
+            positions.iter().map(|&pos| Ball { color: self.color, ..Ball::at(pos, self.radius, self.material) }).collect();
+        let zero = raylib::Vector2::zero();
+        let joints = springs
+            .into_iter()
+            .map(|(i, j): (usize, usize)| {
+                let mut spring = Joint::new(JointKind::Spring, BodyId::Ball(first + i), zero, BodyId::Ball(first + j), zero);
+                spring.length = positions[i].distance(positions[j]);
+                spring.stiffness = self.stiffness;
+                spring.damping = self.damping;
+                spring
+            })
+            .collect();
+
+        // measured on balls first, then pointed at where they'll be
+        let mut soft = SoftBody {
+            points: (0..balls.len()).collect(),
+            rest: Vec::new(),
+            outline,
+            area: 0.0,
+            pressure: self.pressure,
+            shape_stiffness: self.shape_stiffness,
+            // see through, so the springs inside still show
+            color: raylib::Color { a: self.color.a / 3, ..self.color },
+        };
+        let center = soft.center(&balls);
+        soft.rest = balls.iter().map(|ball| ball.pos - center).collect();
+        soft.area = soft.signed_area(&balls).abs();
+        soft.points = (first..first + balls.len()).collect();
+        (balls, joints, soft)
+    }
+}
+
+// The balls of a soft body and the shape they are made in, see Lattice.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoftBody {
+    pub points: Vec<usize>,           // into the world's balls
+    pub rest: Vec<raylib::Vector2>,   // in m, from the center of mass, one for every point
+    pub outline: Vec<usize>,          // into points, going around the edge
+    pub area: f32,                    // in m², inside the outline as made
+    pub pressure: f32,                // in N/m at that area, 0 for none
+    pub shape_stiffness: f32,         // in 1/s², 0 for no shape matching
+    pub color: raylib::Color,         // of the inside
+}
+
+impl SoftBody {
+    // in m, where the points' center of mass is, balls are all of them
+    fn center(&self, balls: &[Ball]) -> raylib::Vector2 {
+        let (mut mass, mut weighted) = (0.0, raylib::Vector2::zero());
+        for &i in self.points.iter() {
+            mass += balls[i].mass;
+            weighted += balls[i].pos * balls[i].mass;
+        }
+        weighted / mass
+    }
+
+    // in m², inside the outline, negative when it goes round the other way
+    fn signed_area(&self, balls: &[Ball]) -> f32 {
+        let pos = |k: usize| balls[self.points[self.outline[k % self.outline.len()]]].pos;
+        (0..self.outline.len()).map(|k| pos(k).cross(pos(k + 1))).sum::<f32>() / 2.0
+    }
+
+    // Changes the points' velocities the way pressure and shape matching
+    // push them over dt, before the solver sees them. sleeping points are
+    // left alone, the whole body sleeps together anyway.
+    pub fn apply(&self, balls: &mut [Ball], dt: f32) {
+        let mut forces = vec![raylib::Vector2::zero(); self.points.len()];
+
+        if self.pressure > 0.0 {
+            // the pressure times the area stays the same, like a gas
+            let area = self.signed_area(balls);
+            let pressure = self.pressure * self.area / area.abs().max(self.area * MIN_AREA);
+            for k in 0..self.outline.len() {
+                let (a, b) = (self.outline[k], self.outline[(k + 1) % self.outline.len()]);
+                let edge = balls[self.points[b]].pos - balls[self.points[a]].pos;
+                // out of the outline and as long as the edge, half to each end
+                let force = -edge.perp() * (area.signum() * pressure / 2.0);
+                forces[a] += force;
+                forces[b] += force;
+            }
+        }
+
+        if self.shape_stiffness > 0.0 {
+            // the rest shape turned by the angle that fits the points best
+            let center = self.center(balls);
+            let (mut dot, mut cross) = (0.0, 0.0);
+            for (&i, rest) in self.points.iter().zip(self.rest.iter()) {
+                let offset = balls[i].pos - center;
+                dot += balls[i].mass * rest.dot(offset);
+                cross += balls[i].mass * rest.cross(offset);
+            }
+            let angle = cross.atan2(dot);
+            // never further than all the way there in one step
+            let stiffness = self.shape_stiffness.min(1.0 / (dt * dt));
+            for ((force, &i), rest) in forces.iter_mut().zip(self.points.iter()).zip(self.rest.iter()) {
+                let goal = center + rest.rotate(angle);
+                *force += (goal - balls[i].pos) * (stiffness * balls[i].mass);
+            }
+        }
+
+        for (force, &i) in forces.into_iter().zip(self.points.iter()) {
+            let ball = &mut balls[i];
+            if !ball.sleep.is_asleep() {
+                ball.velocity += force / ball.mass * dt;
+            }
+        }
+    }
+
+    // filled from its center out to the outline, under the points
+    pub unsafe fn draw(&self, balls: &[Ball], alpha: f32) {
+        let pos = |k: usize| {
+            let ball = &balls[self.points[self.outline[k % self.outline.len()]]];
+            ball.prev_pos.lerp(ball.pos, alpha) * PIXELS_PER_METER
+        };
+        let n = self.outline.len();
+        let center = (0..n).fold(raylib::Vector2::zero(), |sum, k| sum + pos(k)) / n as f32;
+        for k in 0..n {
+            let (a, b) = (pos(k), pos(k + 1));
+            // raylib only fills triangles that go counterclockwise on screen
+            if (a - center).cross(b - center) < 0.0 {
+                raylib::draw_triangle(center, a, b, self.color);
+            } else {
+                raylib::draw_triangle(center, b, a, self.color);
+            }
+        }
+    }
+}
@@ -0,0 +1,646 @@
+// Sequential impulses. Every step the contacts of all bodies are collected
+// first, then solved together: each iteration goes over every contact and
+// corrects the impulse it has applied so far (accumulated, so a later
+// contact can take back what an earlier one overdid), until the impulses
+// agree with each other. The order contacts come in barely matters then,
+// and a pile of balls can hold itself up.
+//
+// Contacts that were there last step start from the impulses they ended
+// with (warm starting), resting contacts hardly change between steps so
+// the solver is most of the way there before the first iteration.
+//
+// Overlap is pushed out with split impulses: a second set of velocities
+// that only moves bodies apart and is thrown away after the step, so
+// fixing penetration never makes anything bounce. Joints that drifted
+// apart are pulled back together the same way.
+
+use std::collections::HashMap;
+
+use crate::ball::Ball;
+use crate::block::Block;
+use crate::contact::{inverse, Manifold};
+use crate::joint::{Joint, JointKind};
+use crate::material::{Friction, Material};
+use crate::obj::RectBody;
+use crate::player::Player;
+use crate::polygon::Polygon;
+
+pub const VELOCITY_ITERATIONS: u32 = 8;
+pub const POSITION_ITERATIONS: u32 = 3;
+
+// contacts closing slower than this in m/s don't bounce, so resting ones
+// settle instead of jittering
+pub const BOUNCE_THRESHOLD: f32 = 0.5;
+
+// how much of the penetration is pushed out per step, and how much of it
+// is left alone so touching bodies stay in contact, in m
+pub const CORRECTION: f32 = 0.2;
+pub const PENETRATION_SLOP: f32 = 0.005;
+
+// an impulse from last step carries over to a contact point that moved
+// less than this on its body, in m
+const WARM_START_DISTANCE: f32 = 0.05;
+
+// Which body a contact belongs to. Fixed is anything that never moves,
+// colliders and hoops.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BodyId {
+    Ball(usize),
+    Block(usize),
+    Polygon(usize),
+    Player,
+    Fixed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SolverSettings {
+    pub velocity_iterations: u32,
+    pub position_iterations: u32,
+    pub warm_starting: bool,
+}
+
+impl Default for SolverSettings {
+    fn default() -> Self {
+        SolverSettings {
+            velocity_iterations: VELOCITY_ITERATIONS,
+            position_iterations: POSITION_ITERATIONS,
+            warm_starting: true,
+        }
+    }
+}
+
+// What a contact point ended the step with, offset is where it was
+// relative to b's center. A ball the hoop swallows shifts the ones after
+// it, their impulses only find the right contact again a step later.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CachedImpulse {
+    pub a: BodyId,
+    pub b: BodyId,
+    pub offset: raylib::Vector2,
+    pub normal: f32,
+    pub tangent: f32,
+    pub rolling: f32,
+}
+
+// A body while the solver works on it, push is the split impulse velocity.
+#[derive(Clone, Copy)]
+struct Motion {
+    center: raylib::Vector2,
+    velocity: raylib::Vector2,
+    angular_velocity: f32,
+    push: raylib::Vector2,
+    angular_push: f32,
+    inv_mass: f32,
+    inv_inertia: f32,
+}
+
+impl Motion {
+    fn new(center: raylib::Vector2, velocity: raylib::Vector2, angular_velocity: f32, inv_mass: f32, inv_inertia: f32) -> Self {
+        Motion {
+            center,
+            velocity,
+            angular_velocity,
+            push: raylib::Vector2::zero(),
+            angular_push: 0.0,
+            inv_mass,
+            inv_inertia,
+        }
+    }
+
+    fn rect<T: RectBody>(body: &T) -> Self {
+        let rect = body.rect();
+        let center = raylib::Vector2::new(rect.x + rect.width / 2.0, rect.y + rect.height / 2.0);
+        Motion::new(center, body.velocity(), body.angular_velocity(), body.inv_mass(), body.inv_inertia())
+    }
+
+    fn fixed() -> Self {
+        Motion::still(raylib::Vector2::zero())
+    }
+
+    // sleeping bodies hold still like fixed ones until they're woken
+    fn still(center: raylib::Vector2) -> Self {
+        Motion::new(center, raylib::Vector2::zero(), 0.0, 0.0, 0.0)
+    }
+
+    // velocity of a point offset from the center, spin included
+    fn point_velocity(&self, offset: raylib::Vector2) -> raylib::Vector2 {
+        self.velocity + offset.perp() * self.angular_velocity
+    }
+
+    fn point_push(&self, offset: raylib::Vector2) -> raylib::Vector2 {
+        self.push + offset.perp() * self.angular_push
+    }
+
+    // how much an impulse along dir at offset is resisted
+    fn inv_effective_mass(&self, offset: raylib::Vector2, dir: raylib::Vector2) -> f32 {
+        let arm = offset.cross(dir);
+        self.inv_mass + arm * arm * self.inv_inertia
+    }
+
+    // twist is an angular impulse on top of offset.cross(impulse)
+    fn apply(&mut self, offset: raylib::Vector2, impulse: raylib::Vector2, twist: f32) {
+        self.velocity += impulse * self.inv_mass;
+        self.angular_velocity += (offset.cross(impulse) + twist) * self.inv_inertia;
+    }
+
+    fn apply_push(&mut self, offset: raylib::Vector2, impulse: raylib::Vector2) {
+        self.push += impulse * self.inv_mass;
+        self.angular_push += offset.cross(impulse) * self.inv_inertia;
+    }
+}
+
+// One contact point between a and b, normal points from a to b. The
+// impulses are what has been applied to b so far, a gets the negative.
+#[derive(Clone, Copy)]
+struct Contact {
+    ids: (BodyId, BodyId),
+    a: usize, // into motions
+    b: usize,
+    normal: raylib::Vector2,
+    tangent: raylib::Vector2,
+    offset_a: raylib::Vector2,
+    offset_b: raylib::Vector2,
+    penetration: f32,
+    bounce: f32, // the speed along the normal it should end up with
+    friction: Friction,
+    normal_mass: f32,
+    tangent_mass: f32,
+    normal_impulse: f32,
+    tangent_impulse: f32,
+    rolling_impulse: f32,
+    push_impulse: f32,
+}
+
+// One joint between a and b, axis points from a's anchor to b's. Springs
+// are soft: gamma is how much they give, bias how hard they pull back.
+#[derive(Clone, Copy)]
+struct JointRow {
+    ids: (BodyId, BodyId),
+    joint: usize, // into the world's joints
+    a: usize,     // into motions
+    b: usize,
+    kind: JointKind,
+    offset_a: raylib::Vector2,
+    offset_b: raylib::Vector2,
+    separation: raylib::Vector2, // from a's anchor to b's, in m
+    axis: raylib::Vector2,
+    error: f32, // in m, how much longer than its length the joint is
+    mass: f32,  // along the axis
+    gamma: f32,
+    bias: f32,
+    impulse: raylib::Vector2,
+    push_impulse: raylib::Vector2,
+}
+
+impl JointRow {
+    // the 2x2 mass matrix of a pin, inverted, None if neither body moves
+    fn pin_mass(&self, a: &Motion, b: &Motion) -> Option<[f32; 4]> {
+        let (ra, rb) = (self.offset_a, self.offset_b);
+        let inv_mass = a.inv_mass + b.inv_mass;
+        let k11 = inv_mass + a.inv_inertia * ra.y * ra.y + b.inv_inertia * rb.y * rb.y;
+        let k12 = -a.inv_inertia * ra.x * ra.y - b.inv_inertia * rb.x * rb.y;
+        let k22 = inv_mass + a.inv_inertia * ra.x * ra.x + b.inv_inertia * rb.x * rb.x;
+        let det = k11 * k22 - k12 * k12;
+        (det != 0.0).then(|| [k22 / det, -k12 / det, -k12 / det, k11 / det])
+    }
+}
+
+fn times(m: [f32; 4], v: raylib::Vector2) -> raylib::Vector2 {
+    raylib::Vector2::new(m[0] * v.x + m[1] * v.y, m[2] * v.x + m[3] * v.y)
+}
+
+fn drop_drift(drift: &mut raylib::Vector2, velocity: raylib::Vector2, solved: raylib::Vector2) {
+    if solved != velocity {
+        *drift = raylib::Vector2::zero();
+    }
+}
+
+// Keeps its buffers between steps, and last step's impulses for warm
+// starting.
+pub struct Solver {
+    pub settings: SolverSettings,
+    pub cache: Vec<CachedImpulse>,
+    motions: Vec<Motion>,
+    contacts: Vec<Contact>,
+    joints: Vec<JointRow>,
+    cached: HashMap<(BodyId, BodyId), Vec<usize>>, // pair -> into cache
+    counts: [usize; 3],                            // balls, blocks, polygons
+}
+
+impl Solver {
+    pub fn new(settings: SolverSettings) -> Self {
+        Solver {
+            settings,
+            cache: Vec::new(),
+            motions: Vec::new(),
+            contacts: Vec::new(),
+            joints: Vec::new(),
+            cached: HashMap::new(),
+            counts: [0; 3],
+        }
+    }
+
+    // how many contact points the last step solved
+    pub fn contact_count(&self) -> usize {
+        self.contacts.len()
+    }
+
+    // how many bodies the last step had, with the player and Fixed last
+    pub fn body_count(&self) -> usize {
+        self.motions.len()
+    }
+
+    // the pairs of bodies touching or jointed in the last step, once per
+    // contact point
+    pub fn pairs(&self) -> impl Iterator<Item = (BodyId, BodyId)> + '_ {
+        let joints = self.joints.iter().map(|joint| joint.ids);
+        self.contacts.iter().map(|contact| contact.ids).chain(joints)
+    }
+
+    // where a body is in motions, balls first, then blocks, polygons, the
+    // player and Fixed
+    pub fn index(&self, id: BodyId) -> usize {
+        let [balls, blocks, polygons] = self.counts;
+        match id {
+            BodyId::Ball(i) => i,
+            BodyId::Block(i) => balls + i,
+            BodyId::Polygon(i) => balls + blocks + i,
+            BodyId::Player => balls + blocks + polygons,
+            BodyId::Fixed => balls + blocks + polygons + 1,
+        }
+    }
+
+    // takes the velocities every body has after gravity and input, the
+    // contacts come after this
+    pub fn begin(&mut self, balls: &[Ball], blocks: &[Block], polygons: &[Polygon], player: &Player) {
+        self.counts = [balls.len(), blocks.len(), polygons.len()];
+        self.motions.clear();
+        self.motions.extend(balls.iter().map(|ball| match ball.sleep.is_asleep() {
+            true => Motion::still(ball.pos),
+            false => Motion::new(ball.pos, ball.velocity, ball.angular_velocity, inverse(ball.mass), ball.inv_inertia()),
+        }));
+        self.motions.extend(blocks.iter().map(|block| match block.sleep.is_asleep() {
+            true => Motion::still(Motion::rect(block).center),
+            false => Motion::rect(block),
+        }));
+        self.motions.extend(polygons.iter().map(|polygon| match polygon.sleep.is_asleep() {
+            true => Motion::still(polygon.pos),
+            false => {
+                let inv_mass = inverse(polygon.mass);
+                Motion::new(polygon.pos, polygon.velocity, polygon.angular_velocity, inv_mass, polygon.inv_inertia())
+            }
+        }));
+        self.motions.push(Motion::rect(player));
+        self.motions.push(Motion::fixed());
+
+        self.contacts.clear();
+        self.joints.clear();
+        self.cached.clear();
+        for (i, cached) in self.cache.iter().enumerate() {
+            self.cached.entry((cached.a, cached.b)).or_default().push(i);
+        }
+    }
+
+    // materials of a and b, the manifold's normal points from a to b
+    pub fn add(&mut self, a: BodyId, b: BodyId, manifold: &Manifold, material_a: Material, material_b: Material) {
+        let (index_a, index_b) = (self.index(a), self.index(b));
+        let (motion_a, motion_b) = (self.motions[index_a], self.motions[index_b]);
+        if motion_a.inv_mass + motion_b.inv_mass == 0.0 {
+            return;
+        }
+
+        let (elast, friction) = material_a.combine(material_b);
+        let normal = manifold.normal;
+        let tangent = normal.perp();
+        for &(point, penetration) in manifold.points() {
+            let offset_a = point - motion_a.center;
+            let offset_b = point - motion_b.center;
+            let closing = (motion_b.point_velocity(offset_b) - motion_a.point_velocity(offset_a)).dot(normal);
+            let normal_mass =
+                motion_a.inv_effective_mass(offset_a, normal) + motion_b.inv_effective_mass(offset_b, normal);
+            let tangent_mass =
+                motion_a.inv_effective_mass(offset_a, tangent) + motion_b.inv_effective_mass(offset_b, tangent);
+
+            let mut contact = Contact {
+                ids: (a, b),
+                a: index_a,
+                b: index_b,
+                normal,
+                tangent,
+                offset_a,
+                offset_b,
+                penetration,
+                bounce: if closing < -BOUNCE_THRESHOLD { -elast * closing } else { 0.0 },
+                friction,
+                normal_mass: inverse(normal_mass),
+                tangent_mass: inverse(tangent_mass),
+                normal_impulse: 0.0,
+                tangent_impulse: 0.0,
+                rolling_impulse: 0.0,
+                push_impulse: 0.0,
+            };
+            if self.settings.warm_starting {
+                self.warm_start(&mut contact);
+            }
+            self.contacts.push(contact);
+        }
+    }
+
+    // index is where the joint is in the world's joints, anchors are where
+    // its ends are in the world right now
+    pub fn add_joint(&mut self, index: usize, joint: &Joint, anchor_a: raylib::Vector2, anchor_b: raylib::Vector2, dt: f32) {
+        let (index_a, index_b) = (self.index(joint.a), self.index(joint.b));
+        let (motion_a, motion_b) = (self.motions[index_a], self.motions[index_b]);
+        if motion_a.inv_mass + motion_b.inv_mass == 0.0 {
+            return;
+        }
+
+        let separation = anchor_b - anchor_a;
+        let distance = separation.length();
+        let axis = if distance > 0.0 { separation / distance } else { raylib::Vector2::new(0.0, 1.0) };
+        let offset_a = anchor_a - motion_a.center;
+        let offset_b = anchor_b - motion_b.center;
+        let inv_mass = motion_a.inv_effective_mass(offset_a, axis) + motion_b.inv_effective_mass(offset_b, axis);
+
+        let mut row = JointRow {
+            ids: (joint.a, joint.b),
+            joint: index,
+            a: index_a,
+            b: index_b,
+            kind: joint.kind,
+            offset_a,
+            offset_b,
+            separation,
+            axis,
+            error: distance - joint.length,
+            mass: inverse(inv_mass),
+            gamma: 0.0,
+            bias: 0.0,
+            impulse: if self.settings.warm_starting { joint.impulse } else { raylib::Vector2::zero() },
+            push_impulse: raylib::Vector2::zero(),
+        };
+        if joint.kind == JointKind::Spring {
+            // implicit Hooke's law, stiff springs don't blow up
+            let soft = dt * (joint.damping + dt * joint.stiffness);
+            if soft == 0.0 {
+                return;
+            }
+            row.gamma = 1.0 / soft;
+            row.bias = row.error * dt * joint.stiffness * row.gamma;
+            row.mass = inverse(inv_mass + row.gamma);
+        }
+        self.joints.push(row);
+    }
+
+    // the closest point of the same pair last step, if it's close enough
+    fn warm_start(&self, contact: &mut Contact) {
+        let Some(candidates) = self.cached.get(&contact.ids) else {
+            return;
+        };
+        let closest = candidates
+            .iter()
+            .map(|&i| &self.cache[i])
+            .filter(|cached| cached.offset.distance(contact.offset_b) < WARM_START_DISTANCE)
+            .min_by(|p, q| {
+                let (p, q) = (p.offset.distance(contact.offset_b), q.offset.distance(contact.offset_b));
+                p.total_cmp(&q)
+            });
+        if let Some(cached) = closest {
+            contact.normal_impulse = cached.normal;
+            contact.tangent_impulse = cached.tangent;
+            contact.rolling_impulse = cached.rolling;
+        }
+    }
+
+    pub fn solve(&mut self, dt: f32) {
+        // the warm started impulses go in first
+        for i in 0..self.contacts.len() {
+            let contact = self.contacts[i];
+            let impulse = contact.normal * contact.normal_impulse + contact.tangent * contact.tangent_impulse;
+            self.motions[contact.a].apply(contact.offset_a, -impulse, -contact.rolling_impulse);
+            self.motions[contact.b].apply(contact.offset_b, impulse, contact.rolling_impulse);
+        }
+        for i in 0..self.joints.len() {
+            let joint = self.joints[i];
+            let impulse = match joint.kind {
+                JointKind::Pin => joint.impulse,
+                _ => joint.axis * joint.impulse.x,
+            };
+            self.motions[joint.a].apply(joint.offset_a, -impulse, 0.0);
+            self.motions[joint.b].apply(joint.offset_b, impulse, 0.0);
+        }
+
+        for _ in 0..self.settings.velocity_iterations {
+            for i in 0..self.joints.len() {
+                self.solve_joint(i, dt);
+            }
+            for i in 0..self.contacts.len() {
+                self.solve_velocity(i);
+            }
+        }
+        for _ in 0..self.settings.position_iterations {
+            for i in 0..self.joints.len() {
+                self.push_joint(i, dt);
+            }
+            for i in 0..self.contacts.len() {
+                self.solve_push(i, dt);
+            }
+        }
+
+        self.cache.clear();
+        self.cache.extend(self.contacts.iter().map(|contact| CachedImpulse {
+            a: contact.ids.0,
+            b: contact.ids.1,
+            offset: contact.offset_b,
+            normal: contact.normal_impulse,
+            tangent: contact.tangent_impulse,
+            rolling: contact.rolling_impulse,
+        }));
+    }
+
+    fn solve_velocity(&mut self, i: usize) {
+        let contact = &mut self.contacts[i];
+        let (mut a, mut b) = (self.motions[contact.a], self.motions[contact.b]);
+        let (offset_a, offset_b) = (contact.offset_a, contact.offset_b);
+        let relative = |a: &Motion, b: &Motion| b.point_velocity(offset_b) - a.point_velocity(offset_a);
+
+        // friction first, it's limited by the normal impulse so far; it
+        // sticks while that takes less than static friction allows and
+        // slides with dynamic friction past that
+        let sliding = relative(&a, &b).dot(contact.tangent);
+        let mut tangent_impulse = contact.tangent_impulse - sliding * contact.tangent_mass;
+        if tangent_impulse.abs() > contact.friction.static_coef * contact.normal_impulse {
+            let limit = contact.friction.dynamic_coef * contact.normal_impulse;
+            tangent_impulse = tangent_impulse.clamp(-limit, limit);
+        }
+        let change = tangent_impulse - contact.tangent_impulse;
+        contact.tangent_impulse = tangent_impulse;
+        a.apply(contact.offset_a, contact.tangent * -change, 0.0);
+        b.apply(contact.offset_b, contact.tangent * change, 0.0);
+
+        // the bodies may only push each other apart
+        let closing = relative(&a, &b).dot(contact.normal);
+        let normal_impulse = (contact.normal_impulse + (contact.bounce - closing) * contact.normal_mass).max(0.0);
+        let change = normal_impulse - contact.normal_impulse;
+        contact.normal_impulse = normal_impulse;
+        a.apply(contact.offset_a, contact.normal * -change, 0.0);
+        b.apply(contact.offset_b, contact.normal * change, 0.0);
+
+        // rolling friction slows down the spin of one against the other
+        let inv_inertia_sum = a.inv_inertia + b.inv_inertia;
+        if inv_inertia_sum > 0.0 {
+            let limit = contact.friction.rolling * contact.normal_impulse;
+            let spin = b.angular_velocity - a.angular_velocity;
+            let rolling_impulse = (contact.rolling_impulse - spin / inv_inertia_sum).clamp(-limit, limit);
+            let change = rolling_impulse - contact.rolling_impulse;
+            contact.rolling_impulse = rolling_impulse;
+            a.apply(contact.offset_a, raylib::Vector2::zero(), -change);
+            b.apply(contact.offset_b, raylib::Vector2::zero(), change);
+        }
+
+        self.motions[contact.a] = a;
+        self.motions[contact.b] = b;
+    }
+
+    fn solve_joint(&mut self, i: usize, dt: f32) {
+        let row = &mut self.joints[i];
+        let (mut a, mut b) = (self.motions[row.a], self.motions[row.b]);
+        let relative = b.point_velocity(row.offset_b) - a.point_velocity(row.offset_a);
+
+        let impulse = match row.kind {
+            JointKind::Pin => {
+                let Some(mass) = row.pin_mass(&a, &b) else {
+                    return;
+                };
+                let change = times(mass, -relative);
+                row.impulse += change;
+                change
+            }
+            JointKind::Distance => {
+                let change = -relative.dot(row.axis) * row.mass;
+                row.impulse.x += change;
+                row.axis * change
+            }
+            JointKind::Spring => {
+                let change = -(relative.dot(row.axis) + row.bias + row.gamma * row.impulse.x) * row.mass;
+                row.impulse.x += change;
+                row.axis * change
+            }
+            JointKind::Rope => {
+                // a slack rope may close in on its length this step, not more
+                let slack = row.error.min(0.0) / dt;
+                let impulse = (row.impulse.x - (relative.dot(row.axis) + slack) * row.mass).min(0.0);
+                let change = impulse - row.impulse.x;
+                row.impulse.x = impulse;
+                row.axis * change
+            }
+        };
+        a.apply(row.offset_a, -impulse, 0.0);
+        b.apply(row.offset_b, impulse, 0.0);
+
+        self.motions[row.a] = a;
+        self.motions[row.b] = b;
+    }
+
+    // pulls joints that drifted back to their length, springs give instead
+    fn push_joint(&mut self, i: usize, dt: f32) {
+        let row = &mut self.joints[i];
+        let (mut a, mut b) = (self.motions[row.a], self.motions[row.b]);
+        let apart = b.point_push(row.offset_b) - a.point_push(row.offset_a);
+
+        let impulse = match row.kind {
+            JointKind::Spring => return,
+            JointKind::Pin => {
+                let Some(mass) = row.pin_mass(&a, &b) else {
+                    return;
+                };
+                let change = times(mass, -row.separation * (CORRECTION / dt) - apart);
+                row.push_impulse += change;
+                change
+            }
+            JointKind::Distance | JointKind::Rope => {
+                let target = -CORRECTION * row.error / dt;
+                let mut impulse = row.push_impulse.x + (target - apart.dot(row.axis)) * row.mass;
+                if row.kind == JointKind::Rope {
+                    impulse = impulse.min(0.0);
+                }
+                let change = impulse - row.push_impulse.x;
+                row.push_impulse.x = impulse;
+                row.axis * change
+            }
+        };
+        a.apply_push(row.offset_a, -impulse);
+        b.apply_push(row.offset_b, impulse);
+
+        self.motions[row.a] = a;
+        self.motions[row.b] = b;
+    }
+
+    fn solve_push(&mut self, i: usize, dt: f32) {
+        let contact = &mut self.contacts[i];
+        let (mut a, mut b) = (self.motions[contact.a], self.motions[contact.b]);
+
+        let target = CORRECTION * (contact.penetration - PENETRATION_SLOP).max(0.0) / dt;
+        let apart = (b.point_push(contact.offset_b) - a.point_push(contact.offset_a)).dot(contact.normal);
+        let push_impulse = (contact.push_impulse + (target - apart) * contact.normal_mass).max(0.0);
+        let change = push_impulse - contact.push_impulse;
+        contact.push_impulse = push_impulse;
+        a.apply_push(contact.offset_a, contact.normal * -change);
+        b.apply_push(contact.offset_b, contact.normal * change);
+
+        self.motions[contact.a] = a;
+        self.motions[contact.b] = b;
+    }
+
+    // hands the solved velocities back and moves every body apart by its
+    // split impulse, the bodies then move on by their velocity themselves.
+    // bodies it pushed on lose their drift, they move like semi-implicit
+    // Euler whatever the integrator, see integrator.rs
+    pub fn finish(
+        &self,
+        balls: &mut [Ball],
+        blocks: &mut [Block],
+        polygons: &mut [Polygon],
+        player: &mut Player,
+        joints: &mut [Joint],
+        dt: f32,
+    ) {
+        for row in self.joints.iter() {
+            joints[row.joint].impulse = row.impulse;
+        }
+        for (i, ball) in balls.iter_mut().enumerate() {
+            let motion = &self.motions[self.index(BodyId::Ball(i))];
+            drop_drift(&mut ball.drift, ball.velocity, motion.velocity);
+            ball.velocity = motion.velocity;
+            ball.angular_velocity = motion.angular_velocity;
+            ball.pos += motion.push * dt;
+            ball.angle += motion.angular_push * dt;
+        }
+        for (i, block) in blocks.iter_mut().enumerate() {
+            let motion = &self.motions[self.index(BodyId::Block(i))];
+            drop_drift(&mut block.drift, block.velocity, motion.velocity);
+            block.velocity = motion.velocity;
+            block.pos += motion.push * dt;
+        }
+        for (i, polygon) in polygons.iter_mut().enumerate() {
+            let motion = &self.motions[self.index(BodyId::Polygon(i))];
+            drop_drift(&mut polygon.drift, polygon.velocity, motion.velocity);
+            polygon.velocity = motion.velocity;
+            polygon.angular_velocity = motion.angular_velocity;
+            polygon.pos += motion.push * dt;
+            polygon.angle += motion.angular_push * dt;
+        }
+        let motion = &self.motions[self.index(BodyId::Player)];
+        drop_drift(&mut player.drift, player.velocity, motion.velocity);
+        player.velocity = motion.velocity;
+        player.angular_velocity = motion.angular_velocity;
+        player.pos += motion.push * dt;
+        player.angle += motion.angular_push * dt;
+    }
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Solver::new(SolverSettings::default())
+    }
+}
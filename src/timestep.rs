@@ -0,0 +1,60 @@
+pub const PHYSICS_HZ: f32 = 120.0;
+pub const MAX_STEPS_PER_FRAME: u32 = 8;
+
+// Turns the variable frame time into a whole number of fixed physics steps.
+// Whatever is left over is kept for the next frame and used as the blend
+// factor between the previous and the current physics state when drawing.
+pub struct FixedTimestep {
+    pub hz: f32,
+    pub max_steps: u32,
+    pub accumulator: f32, // in s
+}
+
+impl FixedTimestep {
+    pub fn new(hz: f32, max_steps: u32) -> Self {
+        FixedTimestep {
+            hz,
+            max_steps: max_steps.max(1),
+            accumulator: 0.0,
+        }
+    }
+
+    pub fn dt(&self) -> f32 {
+        1.0 / self.hz
+    }
+
+    // returns how many steps of `dt()` have to be simulated this frame
+    pub fn advance(&mut self, frame_time: f32) -> u32 {
+        let dt = self.dt();
+        self.accumulator += frame_time.max(0.0);
+
+        let mut steps = 0;
+        while self.accumulator >= dt && steps < self.max_steps {
+            self.accumulator -= dt;
+            steps += 1;
+        }
+
+        // spiral of death: we can't keep up, so drop the backlog instead of
+        // trying to catch up next frame and falling even further behind
+        if self.accumulator >= dt {
+            self.accumulator %= dt;
+        }
+
+        steps
+    }
+
+    // how far we are between the last two physics states, in [0, 1)
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.dt()
+    }
+
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        FixedTimestep::new(PHYSICS_HZ, MAX_STEPS_PER_FRAME)
+    }
+}
@@ -0,0 +1,778 @@
+use crate::ball::Ball;
+use crate::block::Block;
+use crate::broadphase::Broadphase;
+use crate::collider::Collider;
+use crate::ccd::{stop_at, sweep_circle_circle, sweep_circle_polygon, sweep_polygon_polygon};
+use crate::contact::{polygon_polygon, rect_points};
+use crate::field::{Field, Forces};
+use crate::fluid::{Fluid, Pool};
+use crate::hoop::Hoop;
+use crate::integrator::Integrator;
+use crate::joint::{Bodies, Joint, JointKind, Rope};
+use crate::liquid::Liquid;
+use crate::material::Material;
+use crate::nbody::{merge, Gravitation, Quadtree};
+use crate::obj::{Collision, PhyObj, RectBody};
+use crate::player::{Player, PlayerInput};
+use crate::polygon::Polygon;
+use crate::scene::Scene;
+use crate::sleep::{energy, Islands, Sleep, SLEEP_ACCELERATION, SLEEP_TIME};
+use crate::soft::{Lattice, SoftBody};
+use crate::solver::{BodyId, Solver};
+use crate::timestep::FixedTimestep;
+use aid::Rng;
+
+// Owns everything the simulation advances. Nothing in here touches the
+// window, the GL context, input polling or the frame clock, the caller
+// feeds those in.
+pub struct World {
+    pub bounds: raylib::Vector2,  // in m, walled in on every side
+    pub gravity: raylib::Vector2, // in m/s²
+    pub fields: Vec<Field>,       // on top of gravity
+    pub air: Fluid,               // slows down balls outside the pools
+    pub pools: Vec<Pool>,         // of water or anything else, see fluid.rs
+    // balls pulling on each other, see nbody.rs
+    pub gravitation: Option<Gravitation>,
+    pub colliders: Vec<Collider>, // the walls first, then the scene's
+    pub balls: Vec<Ball>,
+    pub player: Player,
+    pub hoops: Vec<Hoop>,
+    pub blocks: Vec<Block>,
+    pub polygons: Vec<Polygon>,
+    pub joints: Vec<Joint>,
+    pub soft_bodies: Vec<SoftBody>, // their points are balls, see soft.rs
+    pub liquids: Vec<Liquid>,       // so are their particles, see liquid.rs
+    pub timestep: FixedTimestep,
+    pub broadphase: Broadphase,
+    pub solver: Solver,
+    pub integrator: Integrator,
+    pub sleeping: bool,   // whether resting bodies fall asleep, see sleep.rs
+    pub next_island: u32, // names the next island that falls asleep
+    pub scene: Scene,
+    pub seed: u64,
+    pub rng: Rng,
+    pub tick: u64, // steps since the last reset
+    pub time: f64, // in s, simulated time since the last reset
+}
+
+impl World {
+    pub fn new(seed: u64, scene: Scene) -> Self {
+        let mut world = World {
+            bounds: scene.bounds,
+            gravity: scene.gravity,
+            fields: Vec::new(),
+            air: scene.air,
+            pools: Vec::new(),
+            gravitation: scene.gravitation,
+            colliders: Vec::new(),
+            balls: Vec::new(),
+            player: scene.player,
+            hoops: Vec::new(),
+            blocks: Vec::new(),
+            polygons: Vec::new(),
+            joints: Vec::new(),
+            soft_bodies: Vec::new(),
+            liquids: Vec::new(),
+            timestep: FixedTimestep::new(scene.hz, scene.max_steps),
+            broadphase: Broadphase::default(),
+            solver: Solver::new(scene.solver),
+            integrator: scene.integrator,
+            sleeping: scene.sleeping,
+            next_island: 0,
+            scene,
+            seed,
+            rng: Rng::new(seed),
+            tick: 0,
+            time: 0.0,
+        };
+        world.reset(seed);
+        world
+    }
+
+    // puts everything back the way the scene describes it, the same seed
+    // gives the same world again
+    pub fn reset(&mut self, seed: u64) {
+        self.bounds = self.scene.bounds;
+        self.seed = seed;
+        self.rng = Rng::new(seed);
+        self.gravity = self.scene.gravity;
+        self.fields = self.scene.fields.clone();
+        self.air = self.scene.air;
+        self.pools = self.scene.pools.clone();
+        self.gravitation = self.scene.gravitation;
+        self.colliders = Collider::walls(self.bounds, self.scene.edges);
+        self.colliders.extend(self.scene.colliders.iter().cloned());
+        self.balls = self.scene.balls.clone();
+        self.balls.extend(Ball::new_vec(self.scene.random_balls, self.bounds, &mut self.rng));
+        self.player = self.scene.player;
+        self.hoops = self.scene.hoops.clone();
+        self.blocks = self.scene.blocks.clone();
+        self.polygons = self.scene.polygons.clone();
+        self.joints = self.scene.joints.clone();
+        self.soft_bodies = self.scene.soft_bodies.clone();
+        self.liquids = self.scene.liquids.clone();
+        self.solver = Solver::new(self.scene.solver);
+        self.integrator = self.scene.integrator;
+        self.sleeping = self.scene.sleeping;
+        self.next_island = 0;
+        self.timestep = FixedTimestep::new(self.scene.hz, self.scene.max_steps);
+        self.tick = 0;
+        self.time = 0.0;
+    }
+
+    pub fn load_scene(&mut self, scene: Scene) {
+        let seed = scene.seed.unwrap_or(self.seed);
+        self.scene = scene;
+        self.reset(seed);
+    }
+
+    // jump and slam are presses, so they are kept until a step consumes
+    // them, otherwise a frame that runs no steps would swallow them
+    pub fn set_input(&mut self, input: PlayerInput) {
+        let current = &mut self.player.input;
+        current.left = input.left;
+        current.right = input.right;
+        current.jump |= input.jump;
+        current.slam |= input.slam;
+    }
+
+    // gives whatever is at point (in m) the material, balls get their mass
+    // from its density, returns false if nothing is there
+    pub fn set_material_at(&mut self, point: raylib::Vector2, material: Material) -> bool {
+        let ball = self.balls.iter_mut().find(|ball| ball.pos.distance(point) <= ball.radius);
+        if let Some(ball) = ball {
+            ball.set_material(material);
+            let island = ball.sleep.island;
+            self.wake(island);
+            return true;
+        }
+        let block = self.blocks.iter_mut().find(|block| raylib::check_collision_point_rec(point, block.rect()));
+        if let Some(block) = block {
+            block.material = material;
+            let island = block.sleep.island;
+            self.wake(island);
+            return true;
+        }
+        let polygon = self.polygons.iter_mut().find(|polygon| polygon.contains(point));
+        if let Some(polygon) = polygon {
+            polygon.set_material(material);
+            let island = polygon.sleep.island;
+            self.wake(island);
+            return true;
+        }
+        if raylib::check_collision_point_rec(point, self.player.rect()) {
+            self.player.material = material;
+            return true;
+        }
+        false
+    }
+
+    // runs as many fixed steps as fit into frame_time, returns the
+    // interpolation factor to draw with
+    pub fn advance(&mut self, frame_time: f32) -> f32 {
+        let dt = self.timestep.dt();
+        for _ in 0..self.timestep.advance(frame_time) {
+            self.step(dt);
+        }
+
+        self.timestep.alpha()
+    }
+
+    pub fn step(&mut self, dt: f32) {
+        for ball in self.balls.iter_mut() {
+            ball.prev_pos = ball.pos;
+            ball.prev_angle = ball.angle;
+        }
+        for block in self.blocks.iter_mut() {
+            block.prev_pos = block.pos;
+        }
+        for polygon in self.polygons.iter_mut() {
+            polygon.prev_pos = polygon.pos;
+            polygon.prev_angle = polygon.angle;
+        }
+        self.player.prev_pos = self.player.pos;
+        self.player.prev_angle = self.player.angle;
+
+        let integrator = self.integrator;
+        let forces = Forces {
+            gravity: self.gravity,
+            fields: &self.fields,
+            attraction: None,
+            air: self.air,
+            pools: &self.pools,
+        };
+        let tree = self.gravitation.map(|gravitation| Quadtree::new(&self.balls, gravitation));
+        for (i, ball) in self.balls.iter_mut().enumerate().filter(|(_, ball)| !ball.sleep.is_asleep()) {
+            let forces = Forces { attraction: tree.as_ref().map(|tree| (tree, i)), ..forces };
+            ball.integrate(integrator, &forces, dt);
+        }
+        for block in self.blocks.iter_mut().filter(|block| !block.sleep.is_asleep()) {
+            block.integrate(integrator, &forces, dt);
+        }
+        for polygon in self.polygons.iter_mut().filter(|polygon| !polygon.sleep.is_asleep()) {
+            polygon.integrate(integrator, &forces, dt);
+        }
+        self.player.integrate(integrator, &forces, dt);
+        self.player.update_movement(dt);
+        for soft in self.soft_bodies.iter() {
+            soft.apply(&mut self.balls, dt);
+        }
+        for liquid in self.liquids.iter_mut() {
+            liquid.apply(&mut self.balls, &mut self.broadphase, dt);
+        }
+
+        self.solver.begin(&self.balls, &self.blocks, &self.polygons, &self.player);
+        self.add_contacts();
+        self.add_joints(dt);
+        if self.wake_touched() {
+            // the woken bodies take part in this step like any other
+            self.solver.begin(&self.balls, &self.blocks, &self.polygons, &self.player);
+            self.add_contacts();
+            self.add_joints(dt);
+        }
+        self.solver.solve(dt);
+        let (balls, blocks, polygons) = (&mut self.balls, &mut self.blocks, &mut self.polygons);
+        self.solver.finish(balls, blocks, polygons, &mut self.player, &mut self.joints, dt);
+
+        for ball in self.balls.iter_mut().filter(|ball| !ball.sleep.is_asleep()) {
+            ball.update_position(&self.colliders, dt);
+        }
+        for block in self.blocks.iter_mut().filter(|block| !block.sleep.is_asleep()) {
+            block.update_position(&self.colliders, dt);
+        }
+        for polygon in self.polygons.iter_mut().filter(|polygon| !polygon.sleep.is_asleep()) {
+            polygon.update_position(&self.colliders, dt);
+        }
+        self.player.update_position(&self.colliders, dt);
+        self.sweep_balls();
+        self.sweep_player();
+        if self.gravitation.is_some_and(|gravitation| gravitation.merging) {
+            self.merge_balls();
+        }
+        self.update_sleep(dt);
+        self.player.input.jump = false;
+        self.player.input.slam = false;
+        for hoop in self.hoops.iter_mut() {
+            for i in hoop.update(self.bounds, &mut self.balls) {
+                remove_ball_joints(&mut self.joints, i);
+                remove_ball_soft_bodies(&mut self.soft_bodies, i);
+                remove_ball_liquids(&mut self.liquids, i);
+            }
+        }
+
+        self.tick += 1;
+        self.time += dt as f64;
+    }
+
+    // what joints hold on to, see joint.rs
+    pub fn bodies(&self) -> Bodies<'_> {
+        Bodies { balls: &self.balls, blocks: &self.blocks, polygons: &self.polygons, player: &self.player }
+    }
+
+    // a joint between what's at from and at to (in m), see Bodies::attach,
+    // ropes add a chain of balls instead. false if nothing was made
+    pub fn add_joint_at(&mut self, kind: JointKind, from: raylib::Vector2, to: raylib::Vector2) -> bool {
+        if kind == JointKind::Rope {
+            if from == to {
+                return false;
+            }
+            let (balls, joints) = Rope::new(from, to).build(&self.bodies(), self.balls.len());
+            self.balls.extend(balls);
+            self.joints.extend(joints);
+            return true;
+        }
+
+        let Some(joint) = self.bodies().attach(Joint::between(kind, from, to)) else {
+            return false;
+        };
+        self.wake_body(joint.a);
+        self.wake_body(joint.b);
+        self.joints.push(joint);
+        true
+    }
+
+    // the points go to the balls and the springs to the joints
+    pub fn add_soft_body(&mut self, lattice: Lattice) {
+        let (balls, joints, soft) = lattice.build(self.balls.len());
+        self.balls.extend(balls);
+        self.joints.extend(joints);
+        self.soft_bodies.push(soft);
+    }
+
+    // more of the first liquid, or water if there's none yet, filling rect
+    // (in m)
+    pub fn pour(&mut self, rect: raylib::Rectangle) {
+        if self.liquids.is_empty() {
+            self.liquids.push(Liquid::new());
+        }
+        let particles = self.liquids[0].pour(rect, self.balls.len());
+        self.balls.extend(particles);
+    }
+
+    // for every ball, which liquid it's a particle of
+    pub fn liquid_of(&self) -> Vec<Option<usize>> {
+        let mut liquid_of = vec![None; self.balls.len()];
+        for (k, liquid) in self.liquids.iter().enumerate() {
+            for &i in liquid.particles.iter() {
+                liquid_of[i] = Some(k);
+            }
+        }
+        liquid_of
+    }
+
+    fn wake_body(&mut self, id: BodyId) {
+        let island = self.sleep(id).and_then(|sleep| sleep.island);
+        self.wake(island);
+    }
+
+    fn add_joints(&mut self, dt: f32) {
+        let bodies = Bodies { balls: &self.balls, blocks: &self.blocks, polygons: &self.polygons, player: &self.player };
+        for (i, joint) in self.joints.iter().enumerate() {
+            let anchor_a = bodies.to_world(joint.a, joint.anchor_a, 1.0);
+            let anchor_b = bodies.to_world(joint.b, joint.anchor_b, 1.0);
+            self.solver.add_joint(i, joint, anchor_a, anchor_b, dt);
+        }
+    }
+
+    // every touching pair goes to the solver, see solver.rs
+    // pairs where neither body moves are left out, sleeping bodies and static
+    // blocks rest, the player never does. balls that merge don't bounce, the
+    // particles of a liquid keep apart on their own
+    fn add_contacts(&mut self) {
+        let liquid_of = self.liquid_of();
+        let solver = &mut self.solver;
+        let player = &self.player;
+        let rests = |block: &Block| block.is_static() || block.sleep.is_asleep();
+        let merging = self.gravitation.is_some_and(|gravitation| gravitation.merging);
+
+        for (i, ball) in self.balls.iter().enumerate().filter(|(_, ball)| !ball.sleep.is_asleep()) {
+            for collider in self.colliders.iter() {
+                if let Some(manifold) = ball.contact_with_collider(collider) {
+                    solver.add(BodyId::Fixed, BodyId::Ball(i), &manifold, collider.material, ball.material);
+                }
+            }
+        }
+        for &(i, j) in self.broadphase.find_pairs(&self.balls) {
+            let (a, b) = (&self.balls[i], &self.balls[j]);
+            if merging || a.sleep.is_asleep() && b.sleep.is_asleep() || liquid_of[i].is_some() && liquid_of[i] == liquid_of[j] {
+                continue;
+            }
+            if let Some(manifold) = a.contact_with_ball(b) {
+                solver.add(BodyId::Ball(i), BodyId::Ball(j), &manifold, a.material, b.material);
+            }
+        }
+        for (i, ball) in self.balls.iter().enumerate() {
+            if let Some(manifold) = player.contact_with_ball(ball) {
+                solver.add(BodyId::Player, BodyId::Ball(i), &manifold, player.material, ball.material);
+            }
+        }
+
+        for (i, block) in self.blocks.iter().enumerate() {
+            if !rests(block) {
+                for collider in self.colliders.iter() {
+                    if let Some(manifold) = block.contact_with_collider(collider) {
+                        solver.add(BodyId::Fixed, BodyId::Block(i), &manifold, collider.material, block.material);
+                    }
+                }
+            }
+            for (j, ball) in self.balls.iter().enumerate() {
+                if rests(block) && ball.sleep.is_asleep() {
+                    continue;
+                }
+                if let Some(manifold) = block.contact_with_ball(ball) {
+                    solver.add(BodyId::Block(i), BodyId::Ball(j), &manifold, block.material, ball.material);
+                }
+            }
+            for (j, other) in self.blocks.iter().enumerate().skip(i + 1) {
+                if rests(block) && rests(other) {
+                    continue;
+                }
+                if let Some(manifold) = block.contact_with_rect(other) {
+                    solver.add(BodyId::Block(i), BodyId::Block(j), &manifold, block.material, other.material);
+                }
+            }
+            if let Some(manifold) = player.contact_with_rect(block) {
+                solver.add(BodyId::Player, BodyId::Block(i), &manifold, player.material, block.material);
+            }
+        }
+        for collider in self.colliders.iter() {
+            if let Some(manifold) = player.contact_with_collider(collider) {
+                solver.add(BodyId::Fixed, BodyId::Player, &manifold, collider.material, player.material);
+            }
+        }
+        for hoop in self.hoops.iter() {
+            if let Some(manifold) = hoop.contact_with_rect(player) {
+                solver.add(BodyId::Fixed, BodyId::Player, &manifold, hoop.material, player.material);
+            }
+        }
+
+        for (i, polygon) in self.polygons.iter().enumerate() {
+            let id = BodyId::Polygon(i);
+            let asleep = polygon.sleep.is_asleep();
+            if !asleep {
+                for collider in self.colliders.iter() {
+                    if let Some(manifold) = polygon.contact_with_collider(collider) {
+                        solver.add(BodyId::Fixed, id, &manifold, collider.material, polygon.material);
+                    }
+                }
+                for hoop in self.hoops.iter() {
+                    if let Some(manifold) = polygon.contact_with_rect(hoop) {
+                        solver.add(id, BodyId::Fixed, &manifold, polygon.material, hoop.material);
+                    }
+                }
+            }
+            for (j, other) in self.polygons.iter().enumerate().skip(i + 1) {
+                if asleep && other.sleep.is_asleep() {
+                    continue;
+                }
+                if let Some(manifold) = polygon_polygon(polygon, other) {
+                    solver.add(id, BodyId::Polygon(j), &manifold, polygon.material, other.material);
+                }
+            }
+            for (j, ball) in self.balls.iter().enumerate() {
+                if asleep && ball.sleep.is_asleep() {
+                    continue;
+                }
+                if let Some(manifold) = polygon.contact_with_ball(ball) {
+                    solver.add(id, BodyId::Ball(j), &manifold, polygon.material, ball.material);
+                }
+            }
+            for (j, block) in self.blocks.iter().enumerate() {
+                if asleep && rests(block) {
+                    continue;
+                }
+                if let Some(manifold) = polygon.contact_with_rect(block) {
+                    solver.add(id, BodyId::Block(j), &manifold, polygon.material, block.material);
+                }
+            }
+            if let Some(manifold) = polygon.contact_with_rect(player) {
+                solver.add(id, BodyId::Player, &manifold, polygon.material, player.material);
+            }
+        }
+    }
+
+    // Balls that moved too far this step for the contact code to catch stop
+    // at the first body in their way, see ccd.rs. The others have already
+    // moved too, so each sweep is relative to how far the other body went.
+    fn sweep_balls(&mut self) {
+        let zero = raylib::Vector2::zero();
+        let liquid_of = self.liquid_of();
+        for i in 0..self.balls.len() {
+            let ball = self.balls[i];
+            let displacement = ball.pos - ball.prev_pos;
+            if !ball.needs_sweep(displacement) {
+                continue;
+            }
+
+            // (t, how far what was hit moved)
+            let mut first: Option<(f32, raylib::Vector2)> = None;
+            let mut hit = |t: Option<f32>, moved: raylib::Vector2| {
+                if let Some(t) = t.filter(|&t| first.is_none_or(|(first, _)| t < first)) {
+                    first = Some((t, moved));
+                }
+            };
+            let sweep_points = |points: &[raylib::Vector2], moved: raylib::Vector2| {
+                let start: Vec<raylib::Vector2> = points.iter().map(|&point| point - moved).collect();
+                sweep_circle_polygon(ball.prev_pos, ball.radius, displacement - moved, &start)
+            };
+
+            // the player only moves after this
+            hit(sweep_points(&self.player.corners(), zero), zero);
+            for block in self.blocks.iter() {
+                let moved = block.pos - block.prev_pos;
+                hit(sweep_points(&rect_points(block.rect()), moved), moved);
+            }
+            for hoop in self.hoops.iter() {
+                hit(sweep_points(&rect_points(hoop.rect()), zero), zero);
+            }
+            for polygon in self.polygons.iter() {
+                let moved = polygon.pos - polygon.prev_pos;
+                hit(sweep_points(&polygon.vertices(), moved), moved);
+            }
+            for (j, other) in self.balls.iter().enumerate() {
+                let moved = other.pos - other.prev_pos;
+                if j != i && (liquid_of[i].is_none() || liquid_of[i] != liquid_of[j]) {
+                    let t = sweep_circle_circle(ball.prev_pos, ball.radius, displacement - moved, other.prev_pos, other.radius);
+                    hit(t, moved);
+                }
+            }
+
+            if let Some((t, moved)) = first {
+                self.balls[i].pos = stop_at(ball.prev_pos, displacement - moved, t) + moved;
+            }
+        }
+    }
+
+    // touching balls become one, see merge. the lower index keeps it, a
+    // ball that already merged this step waits for the next one
+    fn merge_balls(&mut self) {
+        let pairs = self.broadphase.find_pairs(&self.balls).to_vec();
+        let mut merged = vec![false; self.balls.len()];
+        let mut gone = Vec::new();
+        for (i, j) in pairs {
+            if merged[i] || merged[j] || self.balls[i].contact_with_ball(&self.balls[j]).is_none() {
+                continue;
+            }
+            self.balls[i] = merge(&self.balls[i], &self.balls[j]);
+            merged[i] = true;
+            merged[j] = true;
+            gone.push(j);
+        }
+
+        // highest first so the indices still to go don't move
+        gone.sort_unstable();
+        for &j in gone.iter().rev() {
+            self.balls.remove(j);
+            remove_ball_joints(&mut self.joints, j);
+            remove_ball_soft_bodies(&mut self.soft_bodies, j);
+            remove_ball_liquids(&mut self.liquids, j);
+        }
+    }
+
+    // same for the player, after it moved and everything else is at rest
+    fn sweep_player(&mut self) {
+        let player = &self.player;
+        let displacement = player.pos - player.prev_pos;
+        if !player.needs_sweep(displacement) {
+            return;
+        }
+
+        let start = player.corners_at(player.prev_pos, player.angle);
+        let sweep = |points: &[raylib::Vector2]| sweep_polygon_polygon(&start, displacement, points);
+
+        // balls move towards the player as it moves towards them
+        let balls = self.balls.iter().map(|ball| sweep_circle_polygon(ball.pos, ball.radius, -displacement, &start));
+        let blocks = self.blocks.iter().map(|block| sweep(&rect_points(block.rect())));
+        let hoops = self.hoops.iter().map(|hoop| sweep(&rect_points(hoop.rect())));
+        let polygons = self.polygons.iter().map(|polygon| sweep(&polygon.vertices()));
+
+        let first = balls.chain(blocks).chain(hoops).chain(polygons).flatten().min_by(f32::total_cmp);
+        if let Some(t) = first {
+            self.player.pos = stop_at(self.player.prev_pos, displacement, t);
+        }
+    }
+
+    // wakes every body that fell asleep with island
+    pub fn wake(&mut self, island: Option<u32>) {
+        if island.is_none() {
+            return;
+        }
+        let balls = self.balls.iter_mut().map(|ball| &mut ball.sleep);
+        let blocks = self.blocks.iter_mut().map(|block| &mut block.sleep);
+        let polygons = self.polygons.iter_mut().map(|polygon| &mut polygon.sleep);
+        for sleep in balls.chain(blocks).chain(polygons).filter(|sleep| sleep.island == island) {
+            sleep.wake();
+        }
+    }
+
+    // everything that's asleep wakes up, for when the forces change under it
+    pub fn wake_all(&mut self) {
+        let balls = self.balls.iter_mut().map(|ball| &mut ball.sleep);
+        let blocks = self.blocks.iter_mut().map(|block| &mut block.sleep);
+        let polygons = self.polygons.iter_mut().map(|polygon| &mut polygon.sleep);
+        for sleep in balls.chain(blocks).chain(polygons) {
+            sleep.wake();
+        }
+    }
+
+    pub fn add_field(&mut self, field: Field) {
+        self.fields.push(field);
+        self.wake_all();
+    }
+
+    // the field added last goes, false if there was none
+    pub fn remove_field(&mut self) -> bool {
+        let removed = self.fields.pop().is_some();
+        self.wake_all();
+        removed
+    }
+
+    // balls start or stop pulling on each other, the way the scene says or
+    // the defaults if it doesn't
+    pub fn toggle_gravitation(&mut self) {
+        self.gravitation = match self.gravitation {
+            Some(_) => None,
+            None => Some(self.scene.gravitation.unwrap_or_default()),
+        };
+        self.wake_all();
+    }
+
+    // how many bodies are asleep right now
+    pub fn asleep_count(&self) -> usize {
+        let balls = self.balls.iter().map(|ball| ball.sleep);
+        let blocks = self.blocks.iter().map(|block| block.sleep);
+        let polygons = self.polygons.iter().map(|polygon| polygon.sleep);
+        balls.chain(blocks).chain(polygons).filter(Sleep::is_asleep).count()
+    }
+
+    // None for what never moves, the player never falls asleep
+    fn sleep(&self, id: BodyId) -> Option<Sleep> {
+        match id {
+            BodyId::Ball(i) => Some(self.balls[i].sleep),
+            BodyId::Block(i) if !self.blocks[i].is_static() => Some(self.blocks[i].sleep),
+            BodyId::Polygon(i) => Some(self.polygons[i].sleep),
+            BodyId::Player => Some(self.player.sleep),
+            BodyId::Block(_) | BodyId::Fixed => None,
+        }
+    }
+
+    // a body that moved last step and touches a sleeping island wakes all of
+    // it, bodies resting on a sleeping island leave it asleep
+    fn wake_touched(&mut self) -> bool {
+        let mut islands = Vec::new();
+        for (a, b) in self.solver.pairs() {
+            let (Some(a), Some(b)) = (self.sleep(a), self.sleep(b)) else {
+                continue;
+            };
+            for (sleeping, other) in [(a, b), (b, a)] {
+                if sleeping.is_asleep() && !other.is_asleep() && other.idle == 0.0 {
+                    islands.push(sleeping.island);
+                }
+            }
+        }
+        for &island in islands.iter() {
+            self.wake(island);
+        }
+        !islands.is_empty()
+    }
+
+    // Bodies touching each other in this step form islands, sleeping ones
+    // and the player hold them up like the walls do. An island whose bodies
+    // have all rested for SLEEP_TIME falls asleep as a whole. Nothing does
+    // while the balls pull on each other, see nbody.rs, and liquids never
+    // do, their particles don't touch each other so they'd fall asleep one
+    // by one.
+    fn update_sleep(&mut self, dt: f32) {
+        if !self.sleeping || self.gravitation.is_some() {
+            return;
+        }
+
+        let forces = Forces {
+            gravity: self.gravity,
+            fields: &self.fields,
+            attraction: None,
+            air: self.air,
+            pools: &self.pools,
+        };
+        let liquid_of = self.liquid_of();
+        let mut touching = vec![false; self.balls.len()];
+        for id in self.solver.pairs().flat_map(|(a, b)| [a, b]) {
+            if let BodyId::Ball(i) = id {
+                touching[i] = true;
+            }
+        }
+        for (i, ball) in self.balls.iter_mut().enumerate().filter(|(_, ball)| !ball.sleep.is_asleep()) {
+            // drag only ever slows it down, it holds nothing up
+            let zero = raylib::Vector2::zero();
+            let pushed = || forces.on_ball(ball.pos, zero, ball.radius, ball.mass) / ball.mass;
+            if liquid_of[i].is_some() || !touching[i] && pushed().length() > SLEEP_ACCELERATION {
+                ball.sleep.idle = 0.0;
+                continue;
+            }
+            let energy = energy(ball.velocity, ball.angular_velocity, ball.mass, ball.inertia());
+            ball.sleep.update(energy, dt);
+        }
+        for block in self.blocks.iter_mut().filter(|block| !block.sleep.is_asleep()) {
+            block.sleep.update(energy(block.velocity, 0.0, block.mass, 0.0), dt);
+        }
+        for polygon in self.polygons.iter_mut().filter(|polygon| !polygon.sleep.is_asleep()) {
+            let energy = energy(polygon.velocity, polygon.angular_velocity, polygon.mass, polygon.inertia());
+            polygon.sleep.update(energy, dt);
+        }
+        let player = &mut self.player;
+        let energy = energy(player.velocity, player.angular_velocity, player.mass, player.inertia());
+        player.sleep.update(energy, dt);
+
+        let awake = |sleep: Option<Sleep>| sleep.is_some_and(|sleep| !sleep.is_asleep());
+        let mut islands = Islands::new(self.solver.body_count());
+        for (a, b) in self.solver.pairs() {
+            if a != BodyId::Player && b != BodyId::Player && awake(self.sleep(a)) && awake(self.sleep(b)) {
+                islands.join(self.solver.index(a), self.solver.index(b));
+            }
+        }
+
+        let balls = (0..self.balls.len()).map(BodyId::Ball);
+        let blocks = (0..self.blocks.len()).map(BodyId::Block);
+        let polygons = (0..self.polygons.len()).map(BodyId::Polygon);
+        let ids: Vec<BodyId> = balls.chain(blocks).chain(polygons).filter(|&id| awake(self.sleep(id))).collect();
+        let mut ready = vec![true; self.solver.body_count()];
+        for &id in ids.iter() {
+            if self.sleep(id).is_some_and(|sleep| sleep.idle < SLEEP_TIME) {
+                ready[islands.find(self.solver.index(id))] = false;
+            }
+        }
+
+        // every ready root names a new island for all of its bodies
+        let mut names = vec![None; self.solver.body_count()];
+        for id in ids {
+            let root = islands.find(self.solver.index(id));
+            if !ready[root] {
+                continue;
+            }
+            let island = *names[root].get_or_insert_with(|| {
+                self.next_island += 1;
+                self.next_island
+            });
+            match id {
+                BodyId::Ball(i) => {
+                    let ball = &mut self.balls[i];
+                    ball.velocity = raylib::Vector2::zero();
+                    ball.angular_velocity = 0.0;
+                    ball.sleep.island = Some(island);
+                }
+                BodyId::Block(i) => {
+                    let block = &mut self.blocks[i];
+                    block.velocity = raylib::Vector2::zero();
+                    block.sleep.island = Some(island);
+                }
+                BodyId::Polygon(i) => {
+                    let polygon = &mut self.polygons[i];
+                    polygon.velocity = raylib::Vector2::zero();
+                    polygon.angular_velocity = 0.0;
+                    polygon.sleep.island = Some(island);
+                }
+                BodyId::Player | BodyId::Fixed => {}
+            }
+        }
+    }
+}
+
+// ball i is gone, its joints go with it and the balls after it move down one
+fn remove_ball_joints(joints: &mut Vec<Joint>, i: usize) {
+    joints.retain(|joint| joint.a != BodyId::Ball(i) && joint.b != BodyId::Ball(i));
+    for joint in joints.iter_mut() {
+        for id in [&mut joint.a, &mut joint.b] {
+            if let BodyId::Ball(j) = id {
+                if *j > i {
+                    *j -= 1;
+                }
+            }
+        }
+    }
+}
+
+// a soft body that loses a point falls apart, the rest of its points and
+// springs stay
+fn remove_ball_soft_bodies(soft_bodies: &mut Vec<SoftBody>, i: usize) {
+    soft_bodies.retain(|soft| !soft.points.contains(&i));
+    for point in soft_bodies.iter_mut().flat_map(|soft| soft.points.iter_mut()) {
+        if *point > i {
+            *point -= 1;
+        }
+    }
+}
+
+// a liquid only loses the particle
+fn remove_ball_liquids(liquids: &mut [Liquid], i: usize) {
+    for liquid in liquids.iter_mut() {
+        if let Some(k) = liquid.particles.iter().position(|&particle| particle == i) {
+            liquid.particles.remove(k);
+            liquid.densities.remove(k);
+        }
+        for particle in liquid.particles.iter_mut() {
+            if *particle > i {
+                *particle -= 1;
+            }
+        }
+    }
+}
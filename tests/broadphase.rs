@@ -0,0 +1,53 @@
+// Every broadphase has to find exactly the pairs brute force does, or
+// contacts go missing depending on which one is picked.
+
+use aid::Rng;
+use physim::ball::Ball;
+use physim::broadphase::{Broadphase, BroadphaseKind};
+use physim::material::Material;
+
+// packed tight enough that most balls touch a few others, with sizes that
+// differ so the hash cells are bigger than most of them
+fn dense_balls(count: usize, rng: &mut Rng) -> Vec<Ball> {
+    let side = (count as f32).sqrt() * 0.5;
+    (0..count)
+        .map(|_| {
+            let pos = raylib::Vector2::new(rng.f32() * side, rng.f32() * side);
+            let radius = 0.05 + rng.f32() * rng.f32() * 0.6;
+            Ball::at(pos, radius, Material::RUBBER)
+        })
+        .collect()
+}
+
+fn pairs(kind: BroadphaseKind, balls: &[Ball], margin: f32) -> Vec<(usize, usize)> {
+    Broadphase::new(kind).find_pairs_within(balls, margin).to_vec()
+}
+
+#[test]
+fn every_broadphase_finds_the_same_pairs() {
+    let mut rng = Rng::new(1234);
+    for count in [0, 1, 2, 50, 2_000] {
+        let balls = dense_balls(count, &mut rng);
+        // the margin is what liquids look for neighbors with
+        for margin in [0.0, 0.3] {
+            let expected = pairs(BroadphaseKind::BruteForce, &balls, margin);
+            if count == 2_000 {
+                assert!(expected.len() > count, "only {} pairs, not dense enough", expected.len());
+            }
+            for kind in [BroadphaseKind::SpatialHash, BroadphaseKind::SweepAndPrune] {
+                let found = pairs(kind, &balls, margin);
+                assert!(found == expected, "{} with {count} balls and a margin of {margin}", kind.name());
+            }
+        }
+    }
+}
+
+#[test]
+fn same_balls_in_the_same_place() {
+    let ball = Ball::at(raylib::Vector2::new(1.0, 1.0), 0.5, Material::RUBBER);
+    let balls = vec![ball; 4];
+    let all = vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+    for kind in [BroadphaseKind::SpatialHash, BroadphaseKind::SweepAndPrune, BroadphaseKind::BruteForce] {
+        assert_eq!(pairs(kind, &balls, 0.0), all, "{}", kind.name());
+    }
+}
@@ -0,0 +1,41 @@
+// Everything in assets/scenes/bullets.toml moves far more than its own size
+// every step, none of it may be pushed through the walls around the world.
+
+use physim::scene::Scene;
+use physim::timestep::FixedTimestep;
+use physim::world::World;
+
+const SECONDS: f32 = 3.0;
+
+fn stays_inside(hz: f32) {
+    let scene = Scene::load("assets/scenes/bullets.toml").unwrap();
+    let mut world = World::new(1, scene);
+    world.timestep = FixedTimestep::new(hz, 1);
+
+    let inside = |pos: raylib::Vector2, bounds: raylib::Vector2| {
+        (0.0..=bounds.x).contains(&pos.x) && (0.0..=bounds.y).contains(&pos.y)
+    };
+    for step in 0..(SECONDS * hz) as u32 {
+        world.step(world.timestep.dt());
+        for (i, ball) in world.balls.iter().enumerate() {
+            assert!(inside(ball.pos, world.bounds), "{hz} Hz, step {step}: ball {i} at {:?}", ball.pos);
+        }
+        let center = world.player.pos + world.player.dim / 2.0;
+        assert!(inside(center, world.bounds), "{hz} Hz, step {step}: player at {center:?}");
+    }
+}
+
+#[test]
+fn bullets_stay_inside_at_30_hz() {
+    stays_inside(30.0);
+}
+
+#[test]
+fn bullets_stay_inside_at_60_hz() {
+    stays_inside(60.0);
+}
+
+#[test]
+fn bullets_stay_inside_at_120_hz() {
+    stays_inside(120.0);
+}
@@ -0,0 +1,65 @@
+// The in flight half of benches/energy.rs, checked instead of printed: a
+// ball thrown across a world too big to hit anything in, nothing but
+// gravity on it.
+
+use physim::ball::Ball;
+use physim::integrator::Integrator;
+use physim::material::Material;
+use physim::player::Player;
+use physim::scene::Scene;
+use physim::sleep::energy;
+use physim::world::World;
+
+const SECONDS: f32 = 10.0;
+
+// in % of the energy it started with, after SECONDS
+fn flight_drift(integrator: Integrator) -> f32 {
+    let bounds = raylib::Vector2::new_from(400.0);
+    let ball = Ball {
+        velocity: raylib::Vector2::new(5.0, -40.0),
+        ..Ball::at(raylib::Vector2::new(50.0, 100.0), 0.5, Material::RUBBER)
+    };
+    let scene = Scene {
+        bounds,
+        integrator,
+        sleeping: false,
+        random_balls: 0,
+        balls: vec![ball],
+        player: Player { pos: raylib::Vector2::new(0.0, bounds.y - 2.0), ..Player::new() },
+        hoops: Vec::new(),
+        blocks: Vec::new(),
+        ..Scene::default()
+    };
+    let mut world = World::new(1234, scene);
+
+    // kinetic and potential, per kg, the floor is 0
+    let ball_energy = |world: &World| {
+        let ball = &world.balls[0];
+        let height = world.bounds - ball.pos;
+        energy(ball.velocity, ball.angular_velocity, ball.mass, ball.inertia()) + world.gravity.dot(height)
+    };
+    let start = ball_energy(&world);
+    for _ in 0..(SECONDS * world.timestep.hz) as u32 {
+        world.step(world.timestep.dt());
+    }
+    (ball_energy(&world) - start) / start * 100.0
+}
+
+#[test]
+fn explicit_euler_gains_energy() {
+    assert!(flight_drift(Integrator::ExplicitEuler) > 0.0);
+}
+
+#[test]
+fn semi_implicit_euler_loses_energy() {
+    assert!(flight_drift(Integrator::SemiImplicitEuler) < 0.0);
+}
+
+#[test]
+fn second_order_and_rk4_drift_less_than_euler() {
+    let euler = flight_drift(Integrator::ExplicitEuler).abs().min(flight_drift(Integrator::SemiImplicitEuler).abs());
+    for integrator in [Integrator::VelocityVerlet, Integrator::PositionVerlet, Integrator::Rk4] {
+        let drift = flight_drift(integrator).abs();
+        assert!(drift < euler / 10.0, "{} drifts {drift}%, euler {euler}%", integrator.name());
+    }
+}
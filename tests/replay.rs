@@ -0,0 +1,97 @@
+// A replay has to play back into exactly the world that was recorded, from
+// the start or from wherever seeking lands, and broken files have to be
+// turned away without a panic.
+
+use physim::player::PlayerInput;
+use physim::replay::{Playback, Recorder, Replay, KEYFRAME_INTERVAL};
+use physim::scene::Scene;
+use physim::snapshot::Snapshot;
+use physim::world::World;
+
+const STEPS: u64 = KEYFRAME_INTERVAL * 2 + 300; // across two keyframes
+
+fn world() -> World {
+    World::new(1234, Scene { random_balls: 32, ..Scene::default() })
+}
+
+// some of every key, the same every time
+fn input(step: u64) -> PlayerInput {
+    PlayerInput {
+        left: step % 200 < 60,
+        right: step % 300 > 200,
+        jump: step.is_multiple_of(97),
+        slam: step.is_multiple_of(151),
+    }
+}
+
+// (the replay, the world it ended in)
+fn record() -> (Replay, World) {
+    let mut world = world();
+    let mut recorder = Recorder::start(&world);
+    for step in 0..STEPS {
+        world.player.input = input(step);
+        let dt = world.timestep.dt();
+        recorder.advance(&mut world, dt);
+    }
+    (recorder.replay, world)
+}
+
+fn same(a: &World, b: &World) -> bool {
+    Snapshot::capture(a).to_text() == Snapshot::capture(b).to_text()
+}
+
+#[test]
+fn plays_back_what_was_recorded() {
+    let (replay, recorded) = record();
+    assert_eq!(replay.len(), STEPS);
+    let replay = Replay::from_bytes(&replay.to_bytes()).unwrap();
+
+    // a different seed, everything has to come from the replay
+    let mut world = World::new(1, Scene::default());
+    let mut playback = Playback::start(replay, &mut world);
+    while !playback.at_end(&world) {
+        playback.step(&mut world);
+    }
+    assert!(same(&world, &recorded));
+}
+
+#[test]
+fn seeking_lands_where_playing_does() {
+    let (replay, _) = record();
+    let mut world = world();
+    let mut playback = Playback::start(replay.clone(), &mut world);
+
+    // back past a keyframe, forward past the next one and back to the start
+    for target in [STEPS, KEYFRAME_INTERVAL + 100, KEYFRAME_INTERVAL - 1, STEPS - 10, 5] {
+        playback.seek(&mut world, target);
+        assert_eq!(playback.position(&world), target);
+
+        let mut expected = World::new(1, Scene::default());
+        let mut from_start = Playback::start(replay.clone(), &mut expected);
+        for _ in 0..target {
+            from_start.step(&mut expected);
+        }
+        assert!(same(&world, &expected), "seeking to {target} diverged");
+    }
+}
+
+#[test]
+fn broken_files_are_errors() {
+    let (replay, _) = record();
+    let bytes = replay.to_bytes();
+    for len in 0..bytes.len() {
+        assert!(Replay::from_bytes(&bytes[..len]).is_err(), "cut to {len} bytes");
+    }
+
+    // a snapshot length that runs past the end, or overflows
+    for snapshot_len in [bytes.len() as u64, u64::MAX] {
+        let mut corrupt = bytes.clone();
+        corrupt[12..20].copy_from_slice(&snapshot_len.to_le_bytes());
+        assert!(Replay::from_bytes(&corrupt).is_err());
+    }
+
+    // a key that doesn't exist
+    let mut corrupt = bytes.clone();
+    *corrupt.last_mut().unwrap() = 0xff;
+    assert!(Replay::from_bytes(&corrupt).is_err());
+}
@@ -0,0 +1,44 @@
+// Snapshots and replays store the generator's state, a change to what a
+// seed gives would make every saved one play out differently.
+
+use aid::Rng;
+
+#[test]
+fn matches_the_pcg32_reference() {
+    // pcg32-demo from pcg-random.org, seeded with 42 on stream 54
+    let mut rng = Rng::with_stream(42, 54);
+    let expected = [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e];
+    for value in expected {
+        assert_eq!(rng.next_u32(), value);
+    }
+}
+
+#[test]
+fn same_seed_same_sequence() {
+    let mut rng = Rng::new(1234);
+    for value in [0x43a1f79a, 0xccbb6256, 0x3e90b159, 0xbb6c8bb2] {
+        assert_eq!(rng.next_u32(), value);
+    }
+    assert_eq!(rng, Rng { state: 17638306825031482912, inc: 13005396917011789751 });
+    assert_ne!(Rng::new(1234).next_u64(), Rng::new(1235).next_u64());
+}
+
+#[test]
+fn ranges_stay_inside() {
+    let mut rng = Rng::new(7);
+    for bound in [1, 2, 3, 10, 1000, u32::MAX] {
+        for _ in 0..1000 {
+            assert!(rng.below(bound) < bound);
+        }
+    }
+    assert_eq!(rng.below(0), 0);
+    for (min, max) in [(-5, 5), (0, 1), (i32::MIN, i32::MAX), (100, 101)] {
+        for _ in 0..1000 {
+            assert!((min..max).contains(&rng.range(min, max)));
+        }
+    }
+    assert_eq!(rng.range(3, 3), 3);
+    for _ in 0..10_000 {
+        assert!((0.0..1.0).contains(&rng.f32()));
+    }
+}
@@ -0,0 +1,46 @@
+// A world restored from a snapshot, binary or text, has to carry on exactly
+// like the one it was taken from. Compared through the text form, which has
+// every field by name and floats down to the bit.
+
+use std::fs;
+
+use physim::scene::Scene;
+use physim::snapshot::Snapshot;
+use physim::world::World;
+
+const BEFORE: u32 = 90; // steps before the snapshot, long enough for some to fall asleep
+const AFTER: u32 = 90; // and after
+
+fn run(world: &mut World, steps: u32) {
+    for _ in 0..steps {
+        world.step(world.timestep.dt());
+    }
+}
+
+fn continues_identically(name: &str, scene: Scene) {
+    let mut original = World::new(1234, scene.clone());
+    run(&mut original, BEFORE);
+    let snapshot = Snapshot::capture(&original);
+
+    let from_bytes = Snapshot::from_bytes(&snapshot.to_bytes()).unwrap();
+    let from_text = Snapshot::from_text(&snapshot.to_text()).unwrap();
+    run(&mut original, AFTER);
+    let expected = Snapshot::capture(&original).to_text();
+
+    for (format, restored) in [("binary", from_bytes), ("text", from_text)] {
+        // a different seed, everything it decides has to come from the snapshot
+        let mut world = World::new(1, scene.clone());
+        restored.restore(&mut world);
+        run(&mut world, AFTER);
+        assert!(Snapshot::capture(&world).to_text() == expected, "{name}: the {format} snapshot diverged");
+    }
+}
+
+#[test]
+fn every_scene_continues_identically() {
+    for entry in fs::read_dir("assets/scenes").unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.display().to_string();
+        continues_identically(&name, Scene::load(&name).unwrap());
+    }
+}
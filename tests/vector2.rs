@@ -0,0 +1,70 @@
+// The Vector2 math and collision checks that used to go through raylib's C
+// functions, against values worked out by hand.
+
+use raylib::{check_collision_circle_rec, check_collision_circles, float_equals, Rectangle, Vector2};
+
+fn assert_close(a: Vector2, b: Vector2) {
+    assert!(float_equals(a.x, b.x) && float_equals(a.y, b.y), "{a:?} != {b:?}");
+}
+
+#[test]
+fn length() {
+    assert_eq!(Vector2::new(3.0, 4.0).length(), 5.0);
+    assert_eq!(Vector2::new(-3.0, 4.0).length_squared(), 25.0);
+    assert_eq!(Vector2::zero().length(), 0.0);
+    assert_eq!(Vector2::new(1.0, 1.0).distance(Vector2::new(4.0, 5.0)), 5.0);
+}
+
+#[test]
+fn normalize() {
+    assert_close(Vector2::new(3.0, 4.0).normalize(), Vector2::new(0.6, 0.8));
+    assert_close(Vector2::new(0.0, -2.0).normalize(), Vector2::new(0.0, -1.0));
+    // like Vector2Normalize, zero stays zero instead of turning into NaN
+    assert_eq!(Vector2::zero().normalize(), Vector2::zero());
+}
+
+#[test]
+fn rotate() {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+    assert_close(Vector2::new(1.0, 0.0).rotate(FRAC_PI_2), Vector2::new(0.0, 1.0));
+    assert_close(Vector2::new(1.0, 2.0).rotate(PI), Vector2::new(-1.0, -2.0));
+    let half = std::f32::consts::FRAC_1_SQRT_2;
+    assert_close(Vector2::new(1.0, 0.0).rotate(-FRAC_PI_4), Vector2::new(half, -half));
+    assert_close(Vector2::new(2.0, 3.0).rotate(0.3).rotate(-0.3), Vector2::new(2.0, 3.0));
+}
+
+#[test]
+fn operators() {
+    let (a, b) = (Vector2::new(1.0, 2.0), Vector2::new(3.0, -4.0));
+    assert_eq!(a + b, Vector2::new(4.0, -2.0));
+    assert_eq!(a - b, Vector2::new(-2.0, 6.0));
+    assert_eq!(a * b, Vector2::new(3.0, -8.0));
+    assert_eq!(a * 2.0, 2.0 * a);
+    assert_eq!(b / 2.0, Vector2::new(1.5, -2.0));
+    assert_eq!(-a, Vector2::new(-1.0, -2.0));
+    assert_eq!(a.dot(b), -5.0);
+    assert_eq!(a.cross(b), -10.0);
+    assert_eq!(a.lerp(b, 0.5), Vector2::new(2.0, -1.0));
+}
+
+#[test]
+fn collision_circles() {
+    let center = Vector2::new(0.0, 0.0);
+    assert!(check_collision_circles(center, 1.0, Vector2::new(1.5, 0.0), 1.0));
+    // touching counts, like CheckCollisionCircles
+    assert!(check_collision_circles(center, 1.0, Vector2::new(0.0, 2.0), 1.0));
+    assert!(!check_collision_circles(center, 1.0, Vector2::new(1.5, 1.5), 1.0));
+    assert!(check_collision_circles(center, 5.0, Vector2::new(1.0, 1.0), 0.1));
+}
+
+#[test]
+fn collision_circle_rec() {
+    let rec = Rectangle { x: 0.0, y: 0.0, width: 4.0, height: 2.0 };
+    // inside, next to each side and a corner just out of reach
+    assert!(check_collision_circle_rec(Vector2::new(2.0, 1.0), 0.1, rec));
+    assert!(check_collision_circle_rec(Vector2::new(-0.5, 1.0), 0.6, rec));
+    assert!(!check_collision_circle_rec(Vector2::new(-0.5, 1.0), 0.4, rec));
+    assert!(check_collision_circle_rec(Vector2::new(2.0, 2.5), 0.5, rec));
+    assert!(!check_collision_circle_rec(Vector2::new(4.5, 2.5), 0.7, rec));
+    assert!(check_collision_circle_rec(Vector2::new(4.5, 2.5), 0.71, rec));
+}